@@ -0,0 +1,501 @@
+//! An in-memory card database, indexed for lookups by id, name and
+//! printing.
+
+use magic_core::card::Card;
+use magic_core::traits::{normalize_name, Named};
+use scryfall::object::{self as scryfall_object, Component, Layout};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// One printing of a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Printing {
+    set: String,
+    collector_number: Option<String>,
+    multiverse_ids: Vec<u32>,
+    mtgo_id: Option<u32>,
+    arena_id: Option<u32>,
+    reprint: bool,
+}
+
+impl Printing {
+    /// The set code, as the source gives it.
+    pub fn set(&self) -> &str {
+        &self.set
+    }
+
+    pub fn collector_number(&self) -> Option<&str> {
+        self.collector_number.as_deref()
+    }
+
+    pub fn multiverse_ids(&self) -> &[u32] {
+        &self.multiverse_ids
+    }
+
+    pub fn mtgo_id(&self) -> Option<u32> {
+        self.mtgo_id
+    }
+
+    pub fn arena_id(&self) -> Option<u32> {
+        self.arena_id
+    }
+
+    /// Whether the card was printed in an earlier set.
+    pub fn reprint(&self) -> bool {
+        self.reprint
+    }
+}
+
+/// Whether a card can be played in a format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Legality {
+    Legal,
+    NotLegal,
+    Restricted,
+    Banned,
+}
+
+impl From<scryfall_object::Legality> for Legality {
+    fn from(legality: scryfall_object::Legality) -> Legality {
+        match legality {
+            scryfall_object::Legality::Legal => Legality::Legal,
+            scryfall_object::Legality::NotLegal => Legality::NotLegal,
+            scryfall_object::Legality::Restricted => Legality::Restricted,
+            scryfall_object::Legality::Banned => Legality::Banned,
+        }
+    }
+}
+
+impl From<mtgjson::Legality> for Legality {
+    fn from(legality: mtgjson::Legality) -> Legality {
+        match legality {
+            mtgjson::Legality::Legal => Legality::Legal,
+            mtgjson::Legality::Restricted => Legality::Restricted,
+            mtgjson::Legality::Banned => Legality::Banned,
+        }
+    }
+}
+
+/// A card and every printing of it in a `CardDb`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardEntry {
+    oracle_id: Option<Uuid>,
+    card: Card,
+    legalities: HashMap<String, Legality>,
+    printings: Vec<Printing>,
+}
+
+impl CardEntry {
+    /// Scryfall's id for the card, shared by all its printings. Cards
+    /// loaded from MTGJSON don't have one.
+    pub fn oracle_id(&self) -> Option<Uuid> {
+        self.oracle_id
+    }
+
+    pub fn card(&self) -> &Card {
+        &self.card
+    }
+
+    /// The legality of the card in a format, such as `"modern"`.
+    pub fn legality(&self, format: &str) -> Legality {
+        self.legalities
+            .get(&format.to_lowercase())
+            .cloned()
+            .unwrap_or(Legality::NotLegal)
+    }
+
+    pub fn printings(&self) -> &[Printing] {
+        &self.printings
+    }
+}
+
+/// The position of a printing: its entry and its index in the entry's
+/// printings.
+type PrintingIndex = (usize, usize);
+
+/// Cards loaded from MTGJSON sets or Scryfall bulk data. Every lookup is a
+/// hash map access.
+#[derive(Debug, Clone, Default)]
+pub struct CardDb {
+    entries: Vec<CardEntry>,
+    by_oracle_id: HashMap<Uuid, usize>,
+    by_name: HashMap<String, usize>,
+    by_set_number: HashMap<(String, String), PrintingIndex>,
+    by_multiverse_id: HashMap<u32, PrintingIndex>,
+    by_mtgo_id: HashMap<u32, PrintingIndex>,
+    by_arena_id: HashMap<u32, PrintingIndex>,
+}
+
+impl CardDb {
+    pub fn new() -> CardDb {
+        Default::default()
+    }
+
+    /// Loads the cards of MTGJSON sets, returning the database and the
+    /// cards that couldn't be converted.
+    pub fn from_mtgjson<'a>(
+        sets: impl IntoIterator<Item = &'a mtgjson::Set>,
+    ) -> (CardDb, Vec<mtgjson::ConversionError>) {
+        let mut db = CardDb::new();
+        let errors = sets
+            .into_iter()
+            .flat_map(|set| db.add_mtgjson_set(set))
+            .collect();
+        (db, errors)
+    }
+
+    /// Loads Scryfall cards, such as the contents of a bulk data file,
+    /// returning the database and the cards that couldn't be converted.
+    pub fn from_scryfall<'a>(
+        cards: impl IntoIterator<Item = &'a scryfall_object::Card>,
+    ) -> (CardDb, Vec<scryfall_object::ConversionError>) {
+        let mut db = CardDb::new();
+        let cards: Vec<_> = cards.into_iter().collect();
+        let errors = db.add_scryfall_cards(&cards);
+        (db, errors)
+    }
+
+    /// Adds the cards of an MTGJSON set. Cards are matched to cards
+    /// already in the database by name.
+    pub fn add_mtgjson_set(&mut self, set: &mtgjson::Set) -> Vec<mtgjson::ConversionError> {
+        let mut errors = vec![];
+
+        for group in mtgjson::group_cards(set.cards()) {
+            let cards = match mtgjson::convert_group(&group) {
+                Ok(cards) => cards,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            for card in cards {
+                let name = card.names()[0].to_string();
+                let record = group
+                    .iter()
+                    .find(|record| record.name() == name)
+                    .unwrap_or(&group[0]);
+                let printing = Printing {
+                    set: set.code().to_string(),
+                    collector_number: record.number().map(str::to_string),
+                    multiverse_ids: record
+                        .multiverseid()
+                        .map(|id| id as u32)
+                        .into_iter()
+                        .collect(),
+                    mtgo_id: None,
+                    arena_id: None,
+                    reprint: record.printings().first().map(String::as_str) != Some(set.code()),
+                };
+
+                let key = normalize_name(&card.display_name());
+                let entry = match self.by_name.get(&key) {
+                    Some(&entry) => entry,
+                    None => {
+                        let legalities = record
+                            .legalities()
+                            .iter()
+                            .map(|legality| {
+                                let format = legality.format().to_lowercase();
+                                (format, legality.legality().into())
+                            })
+                            .collect();
+                        self.add_entry(None, card, legalities)
+                    }
+                };
+                self.add_printing(entry, printing);
+            }
+        }
+
+        errors
+    }
+
+    /// Adds Scryfall cards. Printings of a card already in the database
+    /// are matched by oracle id and aren't converted again. Meld cards are
+    /// converted with their meld result, which must be among `cards`.
+    pub fn add_scryfall_cards(
+        &mut self,
+        cards: &[&scryfall_object::Card],
+    ) -> Vec<scryfall_object::ConversionError> {
+        let mut errors = vec![];
+
+        let meld_results: HashMap<&str, &scryfall_object::Card> = cards
+            .iter()
+            .filter(|card| is_meld_result(card))
+            .map(|card| (card.name().as_str(), *card))
+            .collect();
+
+        for card in cards {
+            if is_meld_result(card) {
+                continue;
+            }
+
+            let entry = match self.by_oracle_id.get(&card.oracle_id()) {
+                Some(&entry) => entry,
+                None => {
+                    let converted = match card.layout() {
+                        Layout::Meld => {
+                            let result = card
+                                .all_parts()
+                                .find(|part| part.component() == Component::MeldResult)
+                                .and_then(|part| meld_results.get(part.name().as_str()));
+                            match result {
+                                Some(result) => scryfall_object::meld(card, result),
+                                None => Card::try_from(*card),
+                            }
+                        }
+                        _ => Card::try_from(*card),
+                    };
+
+                    match converted {
+                        Ok(converted) => {
+                            let legalities = card
+                                .legalities()
+                                .formats()
+                                .map(|(format, legality)| (format.to_string(), legality.into()))
+                                .collect();
+                            self.add_entry(Some(card.oracle_id()), converted, legalities)
+                        }
+                        Err(error) => {
+                            errors.push(error);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let printing = Printing {
+                set: card.set().clone(),
+                collector_number: Some(card.collector_number().clone()),
+                multiverse_ids: card.multiverse_ids().to_vec(),
+                mtgo_id: card.mtgo_id(),
+                arena_id: card.arena_id(),
+                reprint: card.reprint(),
+            };
+            self.add_printing(entry, printing);
+        }
+
+        errors
+    }
+
+    /// Adds a card, indexing it by its display name and the name of each
+    /// of its faces. Names already taken keep pointing at their card.
+    fn add_entry(
+        &mut self,
+        oracle_id: Option<Uuid>,
+        card: Card,
+        legalities: HashMap<String, Legality>,
+    ) -> usize {
+        let entry = self.entries.len();
+
+        if let Some(oracle_id) = oracle_id {
+            self.by_oracle_id.insert(oracle_id, entry);
+        }
+
+        let names = std::iter::once(card.display_name())
+            .chain(card.faces().flat_map(|face| face.names()))
+            .map(|name| normalize_name(&name));
+        for name in names {
+            self.by_name.entry(name).or_insert(entry);
+        }
+
+        self.entries.push(CardEntry {
+            oracle_id,
+            card,
+            legalities,
+            printings: vec![],
+        });
+        entry
+    }
+
+    fn add_printing(&mut self, entry: usize, printing: Printing) {
+        let index = (entry, self.entries[entry].printings.len());
+
+        if let Some(number) = &printing.collector_number {
+            let key = (printing.set.to_lowercase(), number.clone());
+            self.by_set_number.insert(key, index);
+        }
+        for &id in &printing.multiverse_ids {
+            self.by_multiverse_id.insert(id, index);
+        }
+        if let Some(id) = printing.mtgo_id {
+            self.by_mtgo_id.insert(id, index);
+        }
+        if let Some(id) = printing.arena_id {
+            self.by_arena_id.insert(id, index);
+        }
+
+        self.entries[entry].printings.push(printing);
+    }
+
+    fn printing(&self, (entry, printing): PrintingIndex) -> (&CardEntry, &Printing) {
+        let entry = &self.entries[entry];
+        (entry, &entry.printings[printing])
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CardEntry> {
+        self.entries.iter()
+    }
+
+    pub fn get_by_oracle_id(&self, oracle_id: Uuid) -> Option<&CardEntry> {
+        self.by_oracle_id
+            .get(&oracle_id)
+            .map(|&entry| &self.entries[entry])
+    }
+
+    /// Finds a card by the name of any of its faces or its display name,
+    /// ignoring case, accents and punctuation.
+    pub fn get_by_name(&self, name: &str) -> Option<&CardEntry> {
+        self.by_name
+            .get(&normalize_name(name))
+            .map(|&entry| &self.entries[entry])
+    }
+
+    /// Finds a printing by set code, ignoring case, and collector number.
+    pub fn get_by_set_number(
+        &self,
+        set: &str,
+        collector_number: &str,
+    ) -> Option<(&CardEntry, &Printing)> {
+        let key = (set.to_lowercase(), collector_number.to_string());
+        self.by_set_number
+            .get(&key)
+            .map(|&index| self.printing(index))
+    }
+
+    pub fn get_by_multiverse_id(&self, id: u32) -> Option<(&CardEntry, &Printing)> {
+        self.by_multiverse_id
+            .get(&id)
+            .map(|&index| self.printing(index))
+    }
+
+    pub fn get_by_mtgo_id(&self, id: u32) -> Option<(&CardEntry, &Printing)> {
+        self.by_mtgo_id.get(&id).map(|&index| self.printing(index))
+    }
+
+    pub fn get_by_arena_id(&self, id: u32) -> Option<(&CardEntry, &Printing)> {
+        self.by_arena_id.get(&id).map(|&index| self.printing(index))
+    }
+}
+
+/// Whether this is the card two meld cards meld into.
+fn is_meld_result(card: &scryfall_object::Card) -> bool {
+    card.all_parts()
+        .any(|part| part.component() == Component::MeldResult && part.name() == card.name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn mtgjson_set(code: &str, cards: Value) -> mtgjson::Set {
+        let set = json!({
+            "border": "black",
+            "cards": cards,
+            "code": code,
+            "name": code,
+            "releaseDate": "2009-10-02",
+            "type": "expansion"
+        });
+        serde_json::from_value(set).unwrap()
+    }
+
+    fn mtgjson_card(name: &str, number: &str, multiverseid: u32) -> Value {
+        mtgjson::fixtures::card(json!({
+            "imageName": name,
+            "multiverseid": multiverseid,
+            "name": name,
+            "number": number,
+        }))
+    }
+
+    fn scryfall_card(
+        name: &str,
+        oracle_id: &str,
+        set: &str,
+        number: &str,
+    ) -> scryfall_object::Card {
+        serde_json::from_value(scryfall::fixtures::card(json!({
+            "oracle_id": oracle_id,
+            "arena_id": 70000 + number.parse::<u32>().unwrap(),
+            "name": name,
+            "type_line": "Instant",
+            "oracle_text": "",
+            "power": null,
+            "toughness": null,
+            "set": set,
+            "set_name": set,
+            "collector_number": number,
+        })))
+        .unwrap()
+    }
+
+    #[test]
+    fn mtgjson_printings() {
+        let zen = mtgjson_set("ZEN", json!([mtgjson_card("Goblin Guide", "126", 170987)]));
+        let a25 = mtgjson_set(
+            "A25",
+            json!([
+                mtgjson_card("Goblin Guide", "133", 442125),
+                mtgjson_card("Goblin Bushwhacker", "134", 442126),
+            ]),
+        );
+
+        let (db, errors) = CardDb::from_mtgjson(vec![&zen, &a25]);
+        assert!(errors.is_empty());
+        assert_eq!(db.len(), 2);
+
+        let guide = db.get_by_name("goblin guide").unwrap();
+        assert_eq!(guide.card().name().unwrap(), "Goblin Guide");
+        assert_eq!(guide.printings().len(), 2);
+
+        let (entry, printing) = db.get_by_set_number("a25", "133").unwrap();
+        assert_eq!(entry, guide);
+        assert_eq!(printing.multiverse_ids(), [442125]);
+
+        let (entry, _) = db.get_by_multiverse_id(442126).unwrap();
+        assert_eq!(entry.card().name().unwrap(), "Goblin Bushwhacker");
+        assert!(db.get_by_multiverse_id(1).is_none());
+    }
+
+    #[test]
+    fn scryfall_printings() {
+        let oracle_id = "44623693-51d6-49ad-8cd7-140505caf02f";
+        let cards = vec![
+            scryfall_card("Lightning Bolt", oracle_id, "lea", "161"),
+            scryfall_card("Lightning Bolt", oracle_id, "m10", "146"),
+            scryfall_card(
+                "Jötun Grunt",
+                "0e3a2bf6-a97c-4ad8-9b0f-4c07ca0a7d64",
+                "csp",
+                "8",
+            ),
+        ];
+
+        let (db, errors) = CardDb::from_scryfall(&cards);
+        assert!(errors.is_empty());
+        assert_eq!(db.len(), 2);
+
+        let bolt = db.get_by_oracle_id(oracle_id.parse().unwrap()).unwrap();
+        assert_eq!(bolt.printings().len(), 2);
+        assert_eq!(bolt.oracle_id(), Some(oracle_id.parse().unwrap()));
+
+        let (entry, printing) = db.get_by_arena_id(70146).unwrap();
+        assert_eq!(entry, bolt);
+        assert_eq!(printing.set(), "m10");
+        assert_eq!(db.get_by_set_number("LEA", "161").unwrap().0, bolt);
+
+        assert!(db.get_by_name("jotun grunt").is_some());
+        assert!(db.get_by_mtgo_id(1).is_none());
+    }
+}
@@ -1 +1,5 @@
-pub use magic_core::*;
\ No newline at end of file
+pub use magic_core::*;
+
+pub mod db;
+pub mod resolve;
+pub mod search;
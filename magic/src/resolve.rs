@@ -0,0 +1,451 @@
+//! Fuzzy resolution of card names, for decklists and other names typed by
+//! players.
+
+use crate::db::CardDb;
+use magic_core::card::Card;
+use magic_core::traits::{normalize_name, Named, NAME_SEPARATOR};
+use std::collections::HashMap;
+
+/// How many completions each trie node keeps, so that autocompletion
+/// doesn't have to walk the names below the prefix.
+const CACHED_COMPLETIONS: usize = 10;
+
+/// The confidence of a match that is only a prefix of a name, before
+/// adding `PREFIX_COVERAGE` times the fraction of the name it covers.
+const PREFIX_CONFIDENCE: f64 = 0.5;
+const PREFIX_COVERAGE: f64 = 0.4;
+
+/// A name that a query may refer to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate<'a> {
+    name: &'a str,
+    confidence: f64,
+}
+
+impl<'a> Candidate<'a> {
+    /// The display name of the card.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// How likely the query is to refer to this card, from 0 to 1. An
+    /// exact match, ignoring case, accents and punctuation, is 1.
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+}
+
+/// A normalized name, or an alias, of a card.
+#[derive(Debug)]
+struct Key {
+    key: String,
+    len: usize,
+    name: usize,
+}
+
+impl Key {
+    /// Completions rank shorter names first.
+    fn rank(&self) -> (usize, &str) {
+        (self.len, &self.key)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: Vec<(char, usize)>,
+    /// The keys that end at this node.
+    keys: Vec<usize>,
+    /// The best ranked keys at or below this node, one per card.
+    completions: Vec<usize>,
+}
+
+/// Resolves misspelled, unaccented, partial or outdated card names.
+///
+/// The display name, the name of every face and the face names joined
+/// with ` // ` are stored normalized in a trie. Queries are matched by
+/// walking the trie with the edit distance to each prefix, so that typos
+/// and names the query is a prefix of (`"Fire"` for `"Fire // Ice"`) are
+/// found without comparing the query to every name.
+#[derive(Debug)]
+pub struct NameResolver {
+    names: Vec<String>,
+    by_name: HashMap<String, usize>,
+    keys: Vec<Key>,
+    nodes: Vec<Node>,
+}
+
+impl NameResolver {
+    pub fn new() -> NameResolver {
+        NameResolver {
+            names: vec![],
+            by_name: HashMap::new(),
+            keys: vec![],
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Adds the names of `card`, which resolve to its display name. A card
+    /// with several faces can also be found by any face, or by the faces
+    /// joined as in Scryfall's names and decklist exports
+    /// (`"Delver of Secrets // Insectile Aberration"`).
+    pub fn add(&mut self, card: &Card) {
+        let name = self.name_index(&card.display_name());
+        self.insert(&card.display_name(), name);
+
+        let mut faces: Vec<String> = vec![];
+        for face in card.faces().flat_map(|face| face.names()) {
+            if !faces.iter().any(|f| *f == face) {
+                faces.push(face.into_owned());
+            }
+        }
+        for face in &faces {
+            self.insert(face, name);
+        }
+        if faces.len() > 1 {
+            self.insert(&faces.join(NAME_SEPARATOR), name);
+        }
+    }
+
+    /// Adds an alias for the card with the display name `name`, such as the
+    /// name it had before an Oracle update.
+    pub fn add_alias(&mut self, alias: &str, name: &str) {
+        let name = self.name_index(name);
+        self.insert(alias, name);
+    }
+
+    fn name_index(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.by_name.get(name) {
+            return index;
+        }
+
+        self.names.push(name.to_string());
+        self.by_name.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    fn insert(&mut self, key: &str, name: usize) {
+        let key = normalize_name(key);
+        if key.is_empty() {
+            return;
+        }
+
+        let existing = self.find(&key).map(|node| &self.nodes[node].keys);
+        if existing.is_some_and(|keys| keys.iter().any(|&k| self.keys[k].name == name)) {
+            return;
+        }
+
+        let index = self.keys.len();
+        self.keys.push(Key {
+            len: key.chars().count(),
+            key,
+            name,
+        });
+
+        let mut node = 0;
+        self.complete(node, index);
+        for c in self.keys[index].key.clone().chars() {
+            let children = &self.nodes[node].children;
+            node = match children.binary_search_by_key(&c, |&(c, _)| c) {
+                Ok(i) => children[i].1,
+                Err(i) => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(i, (c, child));
+                    child
+                }
+            };
+            self.complete(node, index);
+        }
+        self.nodes[node].keys.push(index);
+    }
+
+    /// Adds `key` to the completions of `node` if it ranks high enough.
+    fn complete(&mut self, node: usize, key: usize) {
+        let keys = &self.keys;
+        let completions = &mut self.nodes[node].completions;
+
+        let same_name = completions
+            .iter()
+            .position(|&k| keys[k].name == keys[key].name);
+        if let Some(i) = same_name {
+            if keys[completions[i]].rank() <= keys[key].rank() {
+                return;
+            }
+            completions.remove(i);
+        }
+
+        let i = completions.partition_point(|&k| keys[k].rank() <= keys[key].rank());
+        if i < CACHED_COMPLETIONS {
+            completions.insert(i, key);
+            completions.truncate(CACHED_COMPLETIONS);
+        }
+    }
+
+    /// The node for a normalized prefix.
+    fn find(&self, prefix: &str) -> Option<usize> {
+        prefix.chars().try_fold(0, |node, c| {
+            let children = &self.nodes[node].children;
+            children
+                .binary_search_by_key(&c, |&(c, _)| c)
+                .ok()
+                .map(|i| children[i].1)
+        })
+    }
+
+    /// The cards `query` may refer to, most likely first.
+    ///
+    /// Names within an edit distance of a third of the query's length
+    /// match, as do names starting with something that close to the query.
+    /// Only the shortest names starting with a prefix are considered.
+    pub fn resolve(&self, query: &str) -> Vec<Candidate<'_>> {
+        let query: Vec<char> = normalize_name(query).chars().collect();
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let max_distance = (query.len() / 3).max(1);
+        let row: Vec<usize> = (0..=query.len()).collect();
+        let mut confidences = HashMap::new();
+        self.walk(0, 0, &query, &row, max_distance, &mut confidences);
+
+        let mut candidates: Vec<_> = confidences
+            .into_iter()
+            .map(|(name, confidence)| Candidate {
+                name: &self.names[name],
+                confidence,
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap()
+                .then_with(|| a.name.cmp(b.name))
+        });
+        candidates
+    }
+
+    /// Visits the children of `node`, given the edit distances from each
+    /// prefix of `query` to the prefix `node` stands for.
+    fn walk(
+        &self,
+        node: usize,
+        depth: usize,
+        query: &[char],
+        row: &[usize],
+        max_distance: usize,
+        confidences: &mut HashMap<usize, f64>,
+    ) {
+        for &(c, child) in &self.nodes[node].children {
+            let mut next = Vec::with_capacity(row.len());
+            next.push(row[0] + 1);
+            for j in 1..row.len() {
+                let substitution = row[j - 1] + usize::from(query[j - 1] != c);
+                next.push(substitution.min(row[j] + 1).min(next[j - 1] + 1));
+            }
+
+            let depth = depth + 1;
+            let distance = next[query.len()];
+            if distance <= max_distance {
+                let similarity = |len: usize| 1.0 - distance as f64 / query.len().max(len) as f64;
+
+                for &key in &self.nodes[child].keys {
+                    let key = &self.keys[key];
+                    add(confidences, key.name, similarity(key.len));
+                }
+                for &key in &self.nodes[child].completions {
+                    let key = &self.keys[key];
+                    if key.len > depth {
+                        let coverage = depth as f64 / key.len as f64;
+                        let confidence = PREFIX_CONFIDENCE + PREFIX_COVERAGE * coverage;
+                        add(confidences, key.name, similarity(depth) * confidence);
+                    }
+                }
+            }
+
+            if next.iter().min().is_some_and(|&d| d <= max_distance) {
+                self.walk(child, depth, query, &next, max_distance, confidences);
+            }
+        }
+    }
+
+    /// The `n` shortest names starting with `prefix`, ignoring case, accents
+    /// and punctuation. Up to `CACHED_COMPLETIONS` are found without
+    /// searching the names below the prefix.
+    pub fn autocomplete(&self, prefix: &str, n: usize) -> Vec<&str> {
+        let mut normalized = normalize_name(prefix);
+        if !normalized.is_empty() && prefix.ends_with(char::is_whitespace) {
+            normalized.push(' ');
+        }
+
+        let node = match self.find(&normalized) {
+            Some(node) => node,
+            None => return vec![],
+        };
+
+        if n <= CACHED_COMPLETIONS {
+            return self.nodes[node]
+                .completions
+                .iter()
+                .take(n)
+                .map(|&key| &*self.names[self.keys[key].name])
+                .collect();
+        }
+
+        let mut keys: Vec<usize> = vec![];
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            keys.extend(&self.nodes[node].keys);
+            stack.extend(self.nodes[node].children.iter().map(|&(_, child)| child));
+        }
+        keys.sort_by(|&a, &b| self.keys[a].rank().cmp(&self.keys[b].rank()));
+
+        let mut names: Vec<&str> = vec![];
+        for key in keys {
+            let name = &*self.names[self.keys[key].name];
+            if !names.contains(&name) {
+                names.push(name);
+                if names.len() == n {
+                    break;
+                }
+            }
+        }
+        names
+    }
+}
+
+/// Records `confidence` for a name, keeping the highest.
+fn add(confidences: &mut HashMap<usize, f64>, name: usize, confidence: f64) {
+    let best = confidences.entry(name).or_insert(confidence);
+    *best = best.max(confidence);
+}
+
+impl Default for NameResolver {
+    fn default() -> NameResolver {
+        NameResolver::new()
+    }
+}
+
+impl CardDb {
+    /// A resolver for the names of the cards in this database.
+    pub fn name_resolver(&self) -> NameResolver {
+        let mut resolver = NameResolver::new();
+        for entry in self.iter() {
+            resolver.add(entry.card());
+        }
+        resolver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magic_core::card::{Card, CardData};
+
+    fn card(name: &str, type_line: &str) -> CardData {
+        CardData::builder(name)
+            .type_line(type_line.parse().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn resolver() -> NameResolver {
+        let mut resolver = NameResolver::new();
+        resolver.add(&Card::Split {
+            left: card("Fire", "Instant"),
+            right: card("Ice", "Instant"),
+            fuse: false,
+        });
+        for name in &[
+            "Fireball",
+            "Lightning Bolt",
+            "Lightning Helix",
+            "Lightning Axe",
+        ] {
+            resolver.add(&Card::Normal(card(name, "Instant")));
+        }
+        resolver.add(&Card::Normal(card("Lim-Dûl's Vault", "Instant")));
+        resolver.add(&Card::Normal(card("Pradesh Drifters", "Creature — Human")));
+        resolver.add_alias("Pradesh Gypsies", "Pradesh Drifters");
+        resolver
+    }
+
+    fn names<'a>(candidates: &[Candidate<'a>]) -> Vec<&'a str> {
+        candidates.iter().map(Candidate::name).collect()
+    }
+
+    #[test]
+    fn resolve() {
+        let resolver = resolver();
+
+        let fire = resolver.resolve("Fire");
+        assert_eq!(names(&fire), ["Fire // Ice", "Fireball"]);
+        assert_eq!(fire[0].confidence(), 1.0);
+        assert!(fire[1].confidence() < 0.9);
+
+        assert_eq!(resolver.resolve("fire/ice")[0].name(), "Fire // Ice");
+        assert_eq!(resolver.resolve("lim dul's vault")[0].confidence(), 1.0);
+        assert_eq!(
+            resolver.resolve("Pradesh Gypsies")[0].name(),
+            "Pradesh Drifters"
+        );
+
+        let bolt = resolver.resolve("Lightnig Bolt");
+        assert_eq!(bolt[0].name(), "Lightning Bolt");
+        assert!(bolt[0].confidence() < 1.0 && bolt[0].confidence() > 0.9);
+
+        assert_eq!(
+            names(&resolver.resolve("lightning")),
+            ["Lightning Axe", "Lightning Bolt", "Lightning Helix"]
+        );
+        assert!(resolver.resolve("Goblin Guide").is_empty());
+        assert!(resolver.resolve("").is_empty());
+    }
+
+    #[test]
+    fn every_face() {
+        let mut resolver = NameResolver::new();
+        resolver.add(&Card::DoubleFaced {
+            front: card("Delver of Secrets", "Creature — Human Wizard"),
+            back: card("Insectile Aberration", "Creature — Human Insect"),
+            modal: false,
+        });
+        resolver.add(&Card::Adventure {
+            creature: card("Brazen Borrower", "Creature — Faerie Rogue"),
+            adventure: card("Petty Theft", "Instant — Adventure"),
+        });
+
+        for query in &[
+            "Delver of Secrets",
+            "Insectile Aberration",
+            "Delver of Secrets // Insectile Aberration",
+        ] {
+            let candidates = resolver.resolve(query);
+            assert_eq!(names(&candidates), ["Delver of Secrets"], "{}", query);
+            assert_eq!(candidates[0].confidence(), 1.0);
+        }
+        for query in &["Petty Theft", "Brazen Borrower // Petty Theft"] {
+            let candidates = resolver.resolve(query);
+            assert_eq!(candidates[0].name(), "Brazen Borrower", "{}", query);
+            assert_eq!(candidates[0].confidence(), 1.0);
+        }
+    }
+
+    #[test]
+    fn autocomplete() {
+        let resolver = resolver();
+
+        assert_eq!(
+            resolver.autocomplete("LIGHT", 2),
+            ["Lightning Axe", "Lightning Bolt"]
+        );
+        assert_eq!(resolver.autocomplete("fi", 5), ["Fire // Ice", "Fireball"]);
+        assert_eq!(resolver.autocomplete("fire ", 5), ["Fire // Ice"]);
+        assert_eq!(resolver.autocomplete("ic", 5), ["Fire // Ice"]);
+        assert_eq!(
+            resolver.autocomplete("", 20),
+            resolver.autocomplete("", CACHED_COMPLETIONS)
+        );
+        assert_eq!(resolver.autocomplete("", 20).len(), 7);
+        assert!(resolver.autocomplete("x", 5).is_empty());
+    }
+}
@@ -0,0 +1,591 @@
+//! Local evaluation of Scryfall's search syntax
+//! (<https://scryfall.com/docs/syntax>) against a `CardDb`.
+
+mod parse;
+
+pub use self::parse::{ParseQueryError, ParseQueryErrorKind};
+
+use crate::db::{CardDb, CardEntry, Legality, Printing};
+use magic_core::card::{Card, CardData, KnownValues, Layout};
+use magic_core::mana::{ConvertedManaCost, ManaCost, ManaSymbol};
+use magic_core::traits::{normalize_name, Named};
+use magic_core::type_::{EnchantmentType, Subtype, Supertype, Type};
+use magic_core::ColorIdentity;
+use std::collections::HashMap;
+use std::{fmt, str::FromStr};
+
+/// A comparison between a card's value and the value in a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `:`, which means `=` for numbers and depends on the keyword for
+    /// colors and mana costs.
+    Colon,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    fn compare<T: PartialOrd>(self, a: T, b: T) -> bool {
+        match self {
+            Operator::Colon | Operator::Eq => a == b,
+            Operator::Ne => a != b,
+            Operator::Lt => a < b,
+            Operator::Le => a <= b,
+            Operator::Gt => a > b,
+            Operator::Ge => a >= b,
+        }
+    }
+
+    /// Compares two sets, given whether `a` contains `b` and `b` contains
+    /// `a`. `:` means `colon`.
+    fn compare_sets(self, a_has_b: bool, b_has_a: bool, colon: Operator) -> bool {
+        match self {
+            Operator::Colon => colon.compare_sets(a_has_b, b_has_a, colon),
+            Operator::Eq => a_has_b && b_has_a,
+            Operator::Ne => !(a_has_b && b_has_a),
+            Operator::Ge => a_has_b,
+            Operator::Gt => a_has_b && !b_has_a,
+            Operator::Le => b_has_a,
+            Operator::Lt => b_has_a && !a_has_b,
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator = match self {
+            Operator::Colon => ":",
+            Operator::Eq => "=",
+            Operator::Ne => "!=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+        };
+        f.write_str(operator)
+    }
+}
+
+/// The value of a `c:` or `id:` query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colors {
+    Colors(ColorIdentity),
+    /// A number of colors.
+    Count(usize),
+}
+
+/// A characteristic compared as a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    ManaValue,
+    Power,
+    Toughness,
+    Loyalty,
+}
+
+/// The properties `is:` can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Is {
+    Reprint,
+    Split,
+    Flip,
+    Transform,
+    ModalDfc,
+    DoubleFaced,
+    Meld,
+    Adventure,
+    Leveler,
+    Saga,
+    Prototype,
+    Permanent,
+    Spell,
+    Historic,
+    Vanilla,
+}
+
+/// A single search term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// A bare word or quoted string, which matches part of the name, or an
+    /// exact name with `!`.
+    Name {
+        name: String,
+        exact: bool,
+    },
+    /// `t:`, a card type, supertype or part of the type line.
+    Type(String),
+    /// `o:`, part of the rules text. `~` stands for the card's name.
+    Oracle(String),
+    /// `c:`, where `:` means `>=`.
+    Color(Operator, Colors),
+    /// `id:`, where `:` means `<=`.
+    Identity(Operator, Colors),
+    /// `m:`, where `:` means `>=`.
+    Mana(Operator, ManaCost),
+    /// `devotion:`, the number of mana symbols of any of the colors on
+    /// some face (700.5), where `:` means `>=`.
+    Devotion(Operator, ColorIdentity, usize),
+    Stat(Stat, Operator, i32),
+    /// `f:`, `banned:` and `restricted:`.
+    Format(String, Legality),
+    Set(String),
+    CollectorNumber(String),
+    Is(Is),
+}
+
+/// A parsed search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+    Filter(Filter),
+}
+
+impl Query {
+    pub fn parse(s: &str) -> Result<Query, ParseQueryError> {
+        parse::parse(s)
+    }
+
+    /// Whether any printing of `entry` matches this query, as the hosted
+    /// service decides when showing one result per card.
+    pub fn matches(&self, entry: &CardEntry) -> bool {
+        if entry.printings().is_empty() {
+            return self.eval(entry, None);
+        }
+
+        entry
+            .printings()
+            .iter()
+            .any(|printing| self.eval(entry, Some(printing)))
+    }
+
+    fn eval(&self, entry: &CardEntry, printing: Option<&Printing>) -> bool {
+        match self {
+            Query::And(queries) => queries.iter().all(|query| query.eval(entry, printing)),
+            Query::Or(queries) => queries.iter().any(|query| query.eval(entry, printing)),
+            Query::Not(query) => !query.eval(entry, printing),
+            Query::Filter(filter) => filter.eval(entry, printing),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseQueryError;
+
+    fn from_str(s: &str) -> Result<Query, ParseQueryError> {
+        Query::parse(s)
+    }
+}
+
+impl Filter {
+    fn eval(&self, entry: &CardEntry, printing: Option<&Printing>) -> bool {
+        let card = entry.card();
+
+        match self {
+            Filter::Name { name, exact: true } => card.match_name(name),
+            Filter::Name { name, exact: false } => {
+                let name = normalize_name(name);
+                std::iter::once(card.display_name())
+                    .chain(card.faces().flat_map(|face| face.names()))
+                    .any(|n| normalize_name(&n).contains(&name))
+            }
+            Filter::Type(type_) => card.faces().any(|face| has_type(face, type_)),
+            Filter::Oracle(text) => card.faces().any(|face| {
+                let text = text.replace('~', &face.names()[0]).to_lowercase();
+                face.text().to_lowercase().contains(&text)
+            }),
+            Filter::Color(operator, colors) => {
+                compare_colors(card.colors(), *operator, *colors, Operator::Ge)
+            }
+            Filter::Identity(operator, colors) => {
+                compare_colors(card.color_identity(), *operator, *colors, Operator::Le)
+            }
+            Filter::Mana(operator, mana_cost) => card.faces().any(|face| {
+                let (a, b) = (symbol_counts(face.mana_cost()), symbol_counts(mana_cost));
+                operator.compare_sets(contains(&a, &b), contains(&b, &a), Operator::Ge)
+            }),
+            Filter::Devotion(operator, colors, count) => {
+                let operator = match operator {
+                    Operator::Colon => Operator::Ge,
+                    operator => *operator,
+                };
+                card.faces()
+                    .any(|face| operator.compare(face.mana_cost().devotion_to(*colors), *count))
+            }
+            Filter::Stat(stat, operator, value) => compare_stat(card, *stat, *operator, *value),
+            Filter::Format(format, Legality::Legal) => matches!(
+                entry.legality(format),
+                Legality::Legal | Legality::Restricted
+            ),
+            Filter::Format(format, legality) => entry.legality(format) == *legality,
+            Filter::Set(set) => printing.is_some_and(|p| p.set().eq_ignore_ascii_case(set)),
+            Filter::CollectorNumber(number) => {
+                printing.is_some_and(|p| p.collector_number() == Some(number))
+            }
+            Filter::Is(is) => eval_is(card, *is, printing),
+        }
+    }
+}
+
+/// Whether `face` has a card type or supertype named `type_`, or `type_`
+/// is part of its type line.
+fn has_type(face: &CardData, type_: &str) -> bool {
+    let type_line = face.type_line();
+    let named = |name: &&str| name.eq_ignore_ascii_case(type_);
+
+    if let Some(i) = Type::NAMES.iter().position(named) {
+        return type_line.has_type(Type::ALL[i]);
+    }
+    if let Some(i) = Supertype::NAMES.iter().position(named) {
+        return type_line.has_supertype(Supertype::ALL[i]);
+    }
+
+    type_line
+        .to_string()
+        .to_lowercase()
+        .contains(&type_.to_lowercase())
+}
+
+fn compare_colors(
+    colors: ColorIdentity,
+    operator: Operator,
+    value: Colors,
+    colon: Operator,
+) -> bool {
+    match value {
+        Colors::Colors(value) => {
+            operator.compare_sets(colors.is_superset(value), value.is_superset(colors), colon)
+        }
+        Colors::Count(count) => operator.compare(colors.len(), count),
+    }
+}
+
+/// How many of each symbol a mana cost has, with generic mana counted as
+/// one `{1}` per point.
+fn symbol_counts(mana_cost: &ManaCost) -> HashMap<ManaSymbol, usize> {
+    let mut counts = HashMap::new();
+    for &symbol in mana_cost.symbols() {
+        match symbol {
+            ManaSymbol::Generic(n) => {
+                *counts.entry(ManaSymbol::Generic(1)).or_insert(0) += n as usize
+            }
+            symbol => *counts.entry(symbol).or_insert(0) += 1,
+        }
+    }
+    counts
+}
+
+fn contains(a: &HashMap<ManaSymbol, usize>, b: &HashMap<ManaSymbol, usize>) -> bool {
+    b.iter()
+        .all(|(symbol, &count)| a.get(symbol).is_some_and(|&n| n >= count))
+}
+
+/// Compares a number on any face of `card`. Scryfall counts `*` as 0.
+fn compare_stat(card: &Card, stat: Stat, operator: Operator, value: i32) -> bool {
+    let context = KnownValues::default();
+
+    match stat {
+        Stat::ManaValue => operator.compare(card.converted_mana_cost() as i32, value),
+        Stat::Power => card.faces().any(|face| {
+            face.power()
+                .and_then(|power| power.evaluate(&context))
+                .is_some_and(|power| operator.compare(power, value))
+        }),
+        Stat::Toughness => card.faces().any(|face| {
+            face.toughness()
+                .and_then(|toughness| toughness.evaluate(&context))
+                .is_some_and(|toughness| operator.compare(toughness, value))
+        }),
+        Stat::Loyalty => card.faces().any(|face| {
+            face.loyalty()
+                .and_then(|loyalty| loyalty.evaluate(&context))
+                .is_some_and(|loyalty| operator.compare(loyalty, value))
+        }),
+    }
+}
+
+fn eval_is(card: &Card, is: Is, printing: Option<&Printing>) -> bool {
+    const PERMANENT_TYPES: [Type; 6] = [
+        Type::Artifact,
+        Type::Battle,
+        Type::Creature,
+        Type::Enchantment,
+        Type::Land,
+        Type::Planeswalker,
+    ];
+
+    let type_line = card.faces().next().map(CardData::type_line);
+    let has_any =
+        |types: &[Type]| type_line.is_some_and(|t| types.iter().any(|&ty| t.has_type(ty)));
+
+    match is {
+        Is::Reprint => printing.is_some_and(Printing::reprint),
+        Is::Split => matches!(
+            card.layout(),
+            Layout::Split | Layout::Aftermath | Layout::Room
+        ),
+        Is::Flip => card.layout() == Layout::Flip,
+        Is::Transform => matches!(card.layout(), Layout::Transform | Layout::Battle),
+        Is::ModalDfc => card.layout() == Layout::ModalDfc,
+        Is::DoubleFaced => matches!(card, Card::DoubleFaced { .. } | Card::Meld { .. }),
+        Is::Meld => card.layout() == Layout::Meld,
+        Is::Adventure => card.layout() == Layout::Adventure,
+        Is::Leveler => card.layout() == Layout::Leveler,
+        Is::Saga => card.faces().any(|face| {
+            face.type_line()
+                .has_subtype(Subtype::Enchantment(EnchantmentType::Saga))
+        }),
+        Is::Prototype => card.layout() == Layout::Prototype,
+        Is::Permanent => has_any(&PERMANENT_TYPES),
+        Is::Spell => has_any(&[
+            Type::Artifact,
+            Type::Battle,
+            Type::Creature,
+            Type::Enchantment,
+            Type::Instant,
+            Type::Planeswalker,
+            Type::Sorcery,
+        ]),
+        Is::Historic => card.faces().any(|face| {
+            let type_line = face.type_line();
+            type_line.has_supertype(Supertype::Legendary)
+                || type_line.has_type(Type::Artifact)
+                || type_line.has_subtype(Subtype::Enchantment(EnchantmentType::Saga))
+        }),
+        Is::Vanilla => card
+            .faces()
+            .all(|face| face.type_line().has_type(Type::Creature) && face.text().is_empty()),
+    }
+}
+
+impl CardDb {
+    /// The cards that match `query`, in the order they were added.
+    pub fn search(&self, query: &Query) -> Vec<&CardEntry> {
+        self.iter().filter(|entry| query.matches(entry)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgjson::fixtures::card;
+    use serde_json::json;
+
+    fn db() -> CardDb {
+        let set = json!({
+            "border": "black",
+            "code": "TST",
+            "name": "Test",
+            "releaseDate": "2018-07-13",
+            "type": "expansion",
+            "cards": [
+                card(json!({
+                    "name": "Edric, Spymaster of Trest",
+                    "number": "1",
+                    "manaCost": "{1}{G}{U}",
+                    "type": "Legendary Creature \u{2014} Elf Rogue",
+                    "supertypes": ["Legendary"],
+                    "types": ["Creature"],
+                    "subtypes": ["Elf", "Rogue"],
+                    "power": "2",
+                    "toughness": "2",
+                    "text": "Whenever a creature deals combat damage to one of your opponents, its controller may draw a card.",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "name": "Llanowar Elves",
+                    "number": "2",
+                    "manaCost": "{G}",
+                    "type": "Creature \u{2014} Elf Druid",
+                    "types": ["Creature"],
+                    "subtypes": ["Elf", "Druid"],
+                    "power": "1",
+                    "toughness": "1",
+                    "text": "{T}: Add {G}.",
+                    "printings": ["LEA", "TST"]
+                })),
+                card(json!({
+                    "name": "Opt",
+                    "number": "3",
+                    "manaCost": "{U}",
+                    "type": "Instant",
+                    "types": ["Instant"],
+                    "subtypes": [],
+                    "power": null,
+                    "toughness": null,
+                    "text": "Scry 1.\nDraw a card.",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "name": "Counterspell",
+                    "number": "4",
+                    "manaCost": "{U}{U}",
+                    "type": "Instant",
+                    "types": ["Instant"],
+                    "subtypes": [],
+                    "power": null,
+                    "toughness": null,
+                    "text": "Counter target spell.",
+                    "printings": ["LEA", "TST"],
+                    "legalities": [{ "format": "Legacy", "legality": "Legal" }]
+                })),
+                card(json!({
+                    "name": "Grizzly Bears",
+                    "number": "5",
+                    "manaCost": "{1}{G}",
+                    "type": "Creature \u{2014} Bear",
+                    "types": ["Creature"],
+                    "subtypes": ["Bear"],
+                    "power": "2",
+                    "toughness": "2",
+                    "text": "",
+                    "printings": ["LEA", "TST"]
+                })),
+                card(json!({
+                    "layout": "transform",
+                    "name": "Delver of Secrets",
+                    "names": ["Delver of Secrets", "Insectile Aberration"],
+                    "number": "6a",
+                    "manaCost": "{U}",
+                    "type": "Creature \u{2014} Human Wizard",
+                    "types": ["Creature"],
+                    "subtypes": ["Human", "Wizard"],
+                    "power": "1",
+                    "toughness": "1",
+                    "text": "At the beginning of your upkeep, look at the top card of your library. You may reveal that card. If an instant or sorcery card is revealed this way, transform Delver of Secrets.",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "layout": "transform",
+                    "name": "Insectile Aberration",
+                    "names": ["Delver of Secrets", "Insectile Aberration"],
+                    "number": "6b",
+                    "manaCost": "",
+                    "colors": ["U"],
+                    "type": "Creature \u{2014} Human Insect",
+                    "types": ["Creature"],
+                    "subtypes": ["Human", "Insect"],
+                    "power": "3",
+                    "toughness": "2",
+                    "text": "Flying",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "layout": "meld",
+                    "name": "Graf Rats",
+                    "names": ["Graf Rats", "Midnight Scavengers", "Chittering Host"],
+                    "number": "7",
+                    "manaCost": "{2}{B}",
+                    "type": "Creature \u{2014} Rat",
+                    "types": ["Creature"],
+                    "subtypes": ["Rat"],
+                    "power": "2",
+                    "toughness": "1",
+                    "text": "At the beginning of combat on your turn, if you both own and control Graf Rats and a creature named Midnight Scavengers, exile them, then meld them into Chittering Host.",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "layout": "meld",
+                    "name": "Midnight Scavengers",
+                    "names": ["Graf Rats", "Midnight Scavengers", "Chittering Host"],
+                    "number": "8",
+                    "manaCost": "{4}{B}",
+                    "type": "Creature \u{2014} Human Rogue",
+                    "types": ["Creature"],
+                    "subtypes": ["Human", "Rogue"],
+                    "power": "3",
+                    "toughness": "3",
+                    "text": "When Midnight Scavengers enters, you may return target creature card with mana value 3 or less from your graveyard to your hand.",
+                    "printings": ["TST"]
+                })),
+                card(json!({
+                    "layout": "meld",
+                    "name": "Chittering Host",
+                    "names": ["Graf Rats", "Midnight Scavengers", "Chittering Host"],
+                    "number": "9",
+                    "manaCost": "",
+                    "colors": ["B"],
+                    "type": "Creature \u{2014} Eldrazi Horror",
+                    "types": ["Creature"],
+                    "subtypes": ["Eldrazi", "Horror"],
+                    "power": "5",
+                    "toughness": "6",
+                    "text": "Haste",
+                    "printings": ["TST"]
+                })),
+            ]
+        });
+        let set: mtgjson::Set = serde_json::from_value(set).unwrap();
+
+        let (db, errors) = CardDb::from_mtgjson(vec![&set]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        db
+    }
+
+    fn search(db: &CardDb, query: &str) -> Vec<String> {
+        let query = query.parse().unwrap();
+        db.search(&query)
+            .into_iter()
+            .map(|entry| entry.card().display_name().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn search_cards() {
+        let db = db();
+
+        assert_eq!(
+            search(
+                &db,
+                "t:creature c>=ug mv<=3 o:\"draw a card\" f:modern -is:reprint"
+            ),
+            ["Edric, Spymaster of Trest"]
+        );
+        assert_eq!(
+            search(&db, "t:elf"),
+            ["Edric, Spymaster of Trest", "Llanowar Elves"]
+        );
+        assert_eq!(search(&db, "t:legendary"), ["Edric, Spymaster of Trest"]);
+        assert_eq!(search(&db, "o:draw -t:creature"), ["Opt"]);
+        assert_eq!(
+            search(&db, "c:u c<=u"),
+            ["Opt", "Counterspell", "Delver of Secrets"]
+        );
+        assert_eq!(search(&db, "id:g"), ["Llanowar Elves", "Grizzly Bears"]);
+        assert_eq!(search(&db, "c:m"), ["Edric, Spymaster of Trest"]);
+        assert!(search(&db, "c:c").is_empty());
+        assert_eq!(search(&db, "-c:c").len(), 8);
+        assert_eq!(search(&db, "m:uu"), ["Counterspell"]);
+        assert_eq!(search(&db, "devotion:uu"), ["Counterspell"]);
+        assert_eq!(
+            search(&db, "devotion:{g/u}{g/u}"),
+            ["Edric, Spymaster of Trest", "Counterspell"]
+        );
+        assert_eq!(search(&db, "devotion=g").len(), 3);
+        assert_eq!(
+            search(&db, "m:1g"),
+            ["Edric, Spymaster of Trest", "Grizzly Bears"]
+        );
+        assert_eq!(search(&db, "pow>=2 tou<3 mv=2"), ["Grizzly Bears"]);
+        assert_eq!(search(&db, "is:vanilla"), ["Grizzly Bears"]);
+        assert_eq!(
+            search(&db, "is:dfc"),
+            ["Delver of Secrets", "Graf Rats", "Midnight Scavengers"]
+        );
+        assert_eq!(search(&db, "is:transform"), ["Delver of Secrets"]);
+        assert_eq!(search(&db, "is:meld"), ["Graf Rats", "Midnight Scavengers"]);
+        assert_eq!(
+            search(&db, "is:spell is:reprint t:instant"),
+            ["Counterspell"]
+        );
+        assert_eq!(search(&db, "f:legacy -f:modern"), ["Counterspell"]);
+        assert_eq!(search(&db, "(elves or bears) cn:2"), ["Llanowar Elves"]);
+        assert_eq!(search(&db, "s:tst !opt"), ["Opt"]);
+        assert!(search(&db, "s:lea").is_empty());
+    }
+}
@@ -0,0 +1,636 @@
+use super::{Colors, Filter, Is, Operator, Query, Stat};
+use crate::db::Legality;
+use magic_core::mana::ManaCost;
+use magic_core::ColorIdentity;
+use std::fmt;
+use std::ops::Range;
+
+/// Keywords of the hosted service that can't be evaluated locally, because
+/// `CardDb` doesn't have the data they search.
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "a",
+    "art",
+    "artist",
+    "artists",
+    "arttag",
+    "atag",
+    "b",
+    "block",
+    "border",
+    "cheapest",
+    "cube",
+    "date",
+    "direction",
+    "eur",
+    "flavor",
+    "flavors",
+    "fo",
+    "frame",
+    "ft",
+    "fulloracle",
+    "function",
+    "game",
+    "has",
+    "illustrations",
+    "in",
+    "include",
+    "keyword",
+    "kw",
+    "lang",
+    "language",
+    "layout",
+    "lore",
+    "new",
+    "oracletag",
+    "order",
+    "otag",
+    "papersets",
+    "powtou",
+    "prefer",
+    "prints",
+    "produces",
+    "pt",
+    "r",
+    "rarity",
+    "sets",
+    "st",
+    "stamp",
+    "tix",
+    "unique",
+    "usd",
+    "watermark",
+    "wm",
+    "year",
+];
+
+const OPERATORS: &[(&str, Operator)] = &[
+    ("!=", Operator::Ne),
+    (">=", Operator::Ge),
+    ("<=", Operator::Le),
+    (":", Operator::Colon),
+    ("=", Operator::Eq),
+    (">", Operator::Gt),
+    ("<", Operator::Lt),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseQueryErrorKind {
+    UnknownKeyword(String),
+    /// A keyword of the hosted service that can't be evaluated locally,
+    /// or an `is:` or `not:` value such as `is:commander`.
+    UnsupportedKeyword(String),
+    InvalidOperator {
+        keyword: String,
+        operator: Operator,
+    },
+    InvalidValue {
+        keyword: String,
+        value: String,
+    },
+    UnclosedQuote,
+    UnbalancedParenthesis,
+    /// A search term was expected, as at the end of `t:elf or`.
+    ExpectedTerm,
+}
+
+/// An error in a search query, with the bytes of the query it is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQueryError {
+    kind: ParseQueryErrorKind,
+    span: Range<usize>,
+}
+
+impl ParseQueryError {
+    pub fn kind(&self) -> &ParseQueryErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseQueryErrorKind::UnknownKeyword(keyword) => {
+                write!(f, "unknown keyword `{}`", keyword)
+            }
+            ParseQueryErrorKind::UnsupportedKeyword(keyword) => {
+                write!(f, "the keyword `{}` isn't supported locally", keyword)
+            }
+            ParseQueryErrorKind::InvalidOperator { keyword, operator } => {
+                write!(f, "`{}` can't be used with `{}`", operator, keyword)
+            }
+            ParseQueryErrorKind::InvalidValue { keyword, value } => {
+                write!(f, "invalid value `{}` for `{}`", value, keyword)
+            }
+            ParseQueryErrorKind::UnclosedQuote => write!(f, "unclosed quote"),
+            ParseQueryErrorKind::UnbalancedParenthesis => write!(f, "unbalanced parenthesis"),
+            ParseQueryErrorKind::ExpectedTerm => write!(f, "expected a search term"),
+        }?;
+        write!(f, " at bytes {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseQueryError {}
+
+pub(super) fn parse(s: &str) -> Result<Query, ParseQueryError> {
+    let mut parser = Parser { s, pos: 0 };
+    let query = parser.or()?;
+
+    parser.skip_whitespace();
+    if parser.pos < s.len() {
+        return Err(parser.error(
+            ParseQueryErrorKind::UnbalancedParenthesis,
+            parser.pos..parser.pos + 1,
+        ));
+    }
+
+    Ok(query)
+}
+
+/// A recursive descent parser. `or` binds looser than the implicit `and`,
+/// and `-` negates the term or parenthesized group after it.
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ParseQueryErrorKind, span: Range<usize>) -> ParseQueryError {
+        ParseQueryError { kind, span }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `word` if it is the next word, ignoring case.
+    fn word(&mut self, word: &str) -> bool {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .unwrap_or(rest.len());
+
+        if rest[..end].eq_ignore_ascii_case(word) {
+            self.pos += end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Query, ParseQueryError> {
+        let mut queries = vec![self.and()?];
+        while self.word("or") {
+            queries.push(self.and()?);
+        }
+
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, ParseQueryError> {
+        let mut queries = vec![];
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().is_empty() || self.rest().starts_with(')') {
+                break;
+            }
+
+            let start = self.pos;
+            if self.word("or") {
+                self.pos = start;
+                break;
+            }
+            if self.word("and") {
+                continue;
+            }
+
+            queries.push(self.unary()?);
+        }
+
+        match queries.len() {
+            0 => Err(self.error(ParseQueryErrorKind::ExpectedTerm, self.pos..self.pos)),
+            1 => Ok(queries.remove(0)),
+            _ => Ok(Query::And(queries)),
+        }
+    }
+
+    fn unary(&mut self) -> Result<Query, ParseQueryError> {
+        if self.rest().starts_with('-') {
+            self.pos += 1;
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+
+        if self.rest().starts_with('(') {
+            let open = self.pos;
+            self.pos += 1;
+            let query = self.or()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with(')') {
+                return Err(self.error(ParseQueryErrorKind::UnbalancedParenthesis, open..open + 1));
+            }
+            self.pos += 1;
+            return Ok(query);
+        }
+
+        self.term()
+    }
+
+    /// Reads a quoted string or a word ending at whitespace or `)`.
+    fn value(&mut self) -> Result<String, ParseQueryError> {
+        let rest = self.rest();
+
+        if let Some(quoted) = rest.strip_prefix('"') {
+            return match quoted.find('"') {
+                Some(end) => {
+                    self.pos += end + 2;
+                    Ok(quoted[..end].to_string())
+                }
+                None => Err(self.error(ParseQueryErrorKind::UnclosedQuote, self.pos..self.s.len())),
+            };
+        }
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == ')')
+            .unwrap_or(rest.len());
+        self.pos += end;
+        Ok(rest[..end].to_string())
+    }
+
+    fn term(&mut self) -> Result<Query, ParseQueryError> {
+        let start = self.pos;
+
+        if self.rest().starts_with('!') {
+            self.pos += 1;
+            let name = self.value()?;
+            if name.is_empty() {
+                return Err(self.error(ParseQueryErrorKind::ExpectedTerm, start..start + 1));
+            }
+            return Ok(Query::Filter(Filter::Name { name, exact: true }));
+        }
+
+        let rest = self.rest();
+        let keyword_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let operator = OPERATORS
+            .iter()
+            .find(|(operator, _)| rest[keyword_len..].starts_with(operator));
+
+        match operator {
+            Some(&(symbol, operator)) if keyword_len > 0 => {
+                let keyword = rest[..keyword_len].to_lowercase();
+                self.pos += keyword_len + symbol.len();
+                let value = self.value()?;
+                term(&keyword, operator, value).map_err(|kind| self.error(kind, start..self.pos))
+            }
+            _ => {
+                let name = self.value()?;
+                if name.is_empty() {
+                    return Err(self.error(ParseQueryErrorKind::ExpectedTerm, start..start + 1));
+                }
+                Ok(Query::Filter(Filter::Name { name, exact: false }))
+            }
+        }
+    }
+}
+
+/// Builds the query for `keyword`, `operator` and `value`.
+fn term(keyword: &str, operator: Operator, value: String) -> Result<Query, ParseQueryErrorKind> {
+    let invalid_value = || ParseQueryErrorKind::InvalidValue {
+        keyword: keyword.to_string(),
+        value: value.clone(),
+    };
+    if value.is_empty() {
+        return Err(invalid_value());
+    }
+
+    let text = || match operator {
+        Operator::Colon | Operator::Eq => Ok(value.clone()),
+        _ => Err(ParseQueryErrorKind::InvalidOperator {
+            keyword: keyword.to_string(),
+            operator,
+        }),
+    };
+    let number = || value.parse::<i32>().map_err(|_| invalid_value());
+
+    let filter = match keyword {
+        "name" | "n" => Filter::Name {
+            name: text()?,
+            exact: false,
+        },
+        "t" | "type" => Filter::Type(text()?),
+        "o" | "oracle" => Filter::Oracle(text()?),
+        "c" | "color" => {
+            let (operator, colors) = colors(operator, &value).ok_or_else(invalid_value)?;
+            Filter::Color(operator, colors)
+        }
+        "id" | "identity" | "ci" => {
+            let (operator, colors) = colors(operator, &value).ok_or_else(invalid_value)?;
+            Filter::Identity(operator, colors)
+        }
+        "m" | "mana" => Filter::Mana(operator, mana_cost(&value).ok_or_else(invalid_value)?),
+        "mv" | "cmc" | "manavalue" => Filter::Stat(Stat::ManaValue, operator, number()?),
+        "pow" | "power" => Filter::Stat(Stat::Power, operator, number()?),
+        "tou" | "toughness" => Filter::Stat(Stat::Toughness, operator, number()?),
+        "loy" | "loyalty" => Filter::Stat(Stat::Loyalty, operator, number()?),
+        "f" | "format" | "legal" => Filter::Format(text()?.to_lowercase(), Legality::Legal),
+        "banned" => Filter::Format(text()?.to_lowercase(), Legality::Banned),
+        "restricted" => Filter::Format(text()?.to_lowercase(), Legality::Restricted),
+        "s" | "set" | "e" | "edition" => Filter::Set(text()?),
+        "cn" | "number" => Filter::CollectorNumber(text()?),
+        "devotion" => {
+            let (colors, count) = devotion(&value).ok_or_else(invalid_value)?;
+            Filter::Devotion(operator, colors, count)
+        }
+        "is" | "not" => {
+            let value = text()?.to_lowercase();
+            let is = is(&value).ok_or_else(|| {
+                ParseQueryErrorKind::UnsupportedKeyword(format!("{}:{}", keyword, value))
+            })?;
+            let query = Query::Filter(Filter::Is(is));
+            return Ok(if keyword == "not" {
+                Query::Not(Box::new(query))
+            } else {
+                query
+            });
+        }
+        _ if UNSUPPORTED_KEYWORDS.contains(&keyword) => {
+            return Err(ParseQueryErrorKind::UnsupportedKeyword(keyword.to_string()))
+        }
+        _ => return Err(ParseQueryErrorKind::UnknownKeyword(keyword.to_string())),
+    };
+
+    Ok(Query::Filter(filter))
+}
+
+/// Reads a color value: letters or a name accepted by `ColorIdentity`, a
+/// number of colors, or `m` for multicolored. `c:c` means exactly
+/// colorless, where `>=` would match every card.
+fn colors(operator: Operator, value: &str) -> Option<(Operator, Colors)> {
+    let value = value.to_lowercase();
+
+    if value == "m" || value == "multicolor" {
+        return match operator {
+            Operator::Colon | Operator::Eq => Some((Operator::Ge, Colors::Count(2))),
+            _ => None,
+        };
+    }
+    if let Ok(count) = value.parse() {
+        return Some((operator, Colors::Count(count)));
+    }
+
+    let colors: ColorIdentity = value.parse().ok()?;
+    let operator = match operator {
+        Operator::Colon if colors.is_colorless() => Operator::Eq,
+        operator => operator,
+    };
+    Some((operator, Colors::Colors(colors)))
+}
+
+/// Reads a mana cost, also accepting symbols without braces as in `2WW`.
+fn mana_cost(value: &str) -> Option<ManaCost> {
+    if value.contains('{') {
+        return value.to_uppercase().parse().ok();
+    }
+
+    let mut braced = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        braced.push('{');
+        braced.push(c.to_ascii_uppercase());
+        while c.is_ascii_digit() && chars.peek().is_some_and(char::is_ascii_digit) {
+            braced.push(chars.next().unwrap());
+        }
+        braced.push('}');
+    }
+    braced.parse().ok()
+}
+
+/// Reads a devotion value, such as `ggg` or `{u/b}{u/b}`: a number of
+/// symbols that all have the same colors.
+fn devotion(value: &str) -> Option<(ColorIdentity, usize)> {
+    let mana_cost = mana_cost(value)?;
+    let symbols = mana_cost.symbols();
+    let colors = symbols.first()?.colors();
+
+    if colors.is_colorless() || symbols.iter().any(|symbol| symbol.colors() != colors) {
+        return None;
+    }
+    Some((colors, symbols.len()))
+}
+
+fn is(value: &str) -> Option<Is> {
+    let is = match value {
+        "reprint" => Is::Reprint,
+        "split" => Is::Split,
+        "flip" => Is::Flip,
+        "transform" => Is::Transform,
+        "mdfc" => Is::ModalDfc,
+        "dfc" => Is::DoubleFaced,
+        "meld" => Is::Meld,
+        "adventure" => Is::Adventure,
+        "leveler" => Is::Leveler,
+        "saga" => Is::Saga,
+        "prototype" => Is::Prototype,
+        "permanent" => Is::Permanent,
+        "spell" => Is::Spell,
+        "historic" => Is::Historic,
+        "vanilla" => Is::Vanilla,
+        _ => return None,
+    };
+    Some(is)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(s: &str) -> Query {
+        match parse(s).unwrap() {
+            query @ Query::Filter(_) | query @ Query::Not(_) => query,
+            query => panic!("expected a single term, got {:?}", query),
+        }
+    }
+
+    fn error(s: &str) -> (ParseQueryErrorKind, Range<usize>) {
+        let error = parse(s).unwrap_err();
+        (error.kind().clone(), error.span())
+    }
+
+    #[test]
+    fn terms() {
+        let query = parse("t:creature c>=ug mv<=3 o:\"draw a card\" f:modern -is:reprint");
+        assert_eq!(
+            query.unwrap(),
+            Query::And(vec![
+                Query::Filter(Filter::Type("creature".to_string())),
+                Query::Filter(Filter::Color(
+                    Operator::Ge,
+                    Colors::Colors("ug".parse().unwrap())
+                )),
+                Query::Filter(Filter::Stat(Stat::ManaValue, Operator::Le, 3)),
+                Query::Filter(Filter::Oracle("draw a card".to_string())),
+                Query::Filter(Filter::Format("modern".to_string(), Legality::Legal)),
+                Query::Not(Box::new(Query::Filter(Filter::Is(Is::Reprint)))),
+            ])
+        );
+
+        assert_eq!(
+            filter("!\"Lightning Bolt\""),
+            Query::Filter(Filter::Name {
+                name: "Lightning Bolt".to_string(),
+                exact: true,
+            })
+        );
+        assert_eq!(
+            filter("M:2ww"),
+            Query::Filter(Filter::Mana(Operator::Colon, "{2}{W}{W}".parse().unwrap()))
+        );
+        assert_eq!(
+            filter("id:m"),
+            Query::Filter(Filter::Identity(Operator::Ge, Colors::Count(2)))
+        );
+        assert_eq!(
+            filter("c:c"),
+            Query::Filter(Filter::Color(
+                Operator::Eq,
+                Colors::Colors(ColorIdentity::new())
+            ))
+        );
+        assert_eq!(
+            filter("-c:colorless"),
+            Query::Not(Box::new(Query::Filter(Filter::Color(
+                Operator::Eq,
+                Colors::Colors(ColorIdentity::new())
+            ))))
+        );
+        assert_eq!(
+            filter("devotion>={u/b}{u/b}"),
+            Query::Filter(Filter::Devotion(Operator::Ge, "Dimir".parse().unwrap(), 2))
+        );
+        assert_eq!(
+            filter("devotion:ggg"),
+            Query::Filter(Filter::Devotion(Operator::Colon, "g".parse().unwrap(), 3))
+        );
+        assert_eq!(
+            filter("not:vanilla"),
+            Query::Not(Box::new(Query::Filter(Filter::Is(Is::Vanilla))))
+        );
+    }
+
+    #[test]
+    fn grouping() {
+        let elf_or_goblin = Query::Or(vec![
+            Query::Filter(Filter::Type("elf".to_string())),
+            Query::Filter(Filter::Type("goblin".to_string())),
+        ]);
+        let not_red = Query::Not(Box::new(Query::Filter(Filter::Color(
+            Operator::Colon,
+            Colors::Colors("r".parse().unwrap()),
+        ))));
+
+        assert_eq!(
+            parse("(t:elf OR t:goblin) -c:r").unwrap(),
+            Query::And(vec![elf_or_goblin.clone(), not_red.clone()])
+        );
+        assert_eq!(
+            parse("t:elf or t:goblin and -c:r").unwrap(),
+            Query::Or(vec![
+                Query::Filter(Filter::Type("elf".to_string())),
+                Query::And(vec![
+                    Query::Filter(Filter::Type("goblin".to_string())),
+                    not_red,
+                ]),
+            ])
+        );
+        assert_eq!(
+            parse("orcish").unwrap(),
+            Query::Filter(Filter::Name {
+                name: "orcish".to_string(),
+                exact: false,
+            })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        use self::ParseQueryErrorKind::*;
+
+        assert_eq!(
+            error("t:elf usd<1"),
+            (UnsupportedKeyword("usd".to_string()), 6..11)
+        );
+        assert_eq!(error("foo:bar"), (UnknownKeyword("foo".to_string()), 0..7));
+        for (query, keyword) in &[
+            ("pt:5", "pt"),
+            ("powtou>4", "powtou"),
+            ("has:watermark", "has"),
+            ("stamp:oval", "stamp"),
+            ("layout:saga", "layout"),
+        ] {
+            assert_eq!(error(query).0, UnsupportedKeyword(keyword.to_string()));
+        }
+        assert_eq!(
+            error("is:commander"),
+            (UnsupportedKeyword("is:commander".to_string()), 0..12)
+        );
+        assert_eq!(
+            error("-not:funny"),
+            (UnsupportedKeyword("not:funny".to_string()), 1..10)
+        );
+        assert_eq!(
+            error("devotion:2g"),
+            (
+                InvalidValue {
+                    keyword: "devotion".to_string(),
+                    value: "2g".to_string(),
+                },
+                0..11
+            )
+        );
+        assert_eq!(
+            error("t>elf"),
+            (
+                InvalidOperator {
+                    keyword: "t".to_string(),
+                    operator: Operator::Gt,
+                },
+                0..5
+            )
+        );
+        assert_eq!(
+            error("mv>x"),
+            (
+                InvalidValue {
+                    keyword: "mv".to_string(),
+                    value: "x".to_string(),
+                },
+                0..4
+            )
+        );
+        assert_eq!(error("o:\"draw"), (UnclosedQuote, 2..7));
+        assert_eq!(error("(t:elf"), (UnbalancedParenthesis, 0..1));
+        assert_eq!(error("t:elf)"), (UnbalancedParenthesis, 5..6));
+        assert_eq!(error("t:elf or"), (ExpectedTerm, 8..8));
+        assert_eq!(error("!"), (ExpectedTerm, 0..1));
+        assert_eq!(error("t:elf ! o:draw"), (ExpectedTerm, 6..7));
+
+        assert_eq!(
+            parse("r:mythic").unwrap_err().to_string(),
+            "the keyword `r` isn't supported locally at bytes 0..8"
+        );
+    }
+}
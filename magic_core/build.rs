@@ -0,0 +1,140 @@
+//! Generates the type enums in `type_` from `data/CardTypes.json`, which has
+//! the same shape as MTGJSON's `CardTypes.json`.
+
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const CATALOG: &str = "data/CardTypes.json";
+
+/// A subtype family: the enum name, its `Subtype` variant, its doc
+/// comment and the card types whose subtypes it holds. The family is valid
+/// for exactly those card types.
+struct Family {
+    name: &'static str,
+    variant: &'static str,
+    doc: &'static str,
+    types: &'static [&'static str],
+}
+
+#[rustfmt::skip]
+const FAMILIES: &[Family] = &[
+    Family { name: "ArtifactType", variant: "Artifact", doc: "Artifact subtypes (205.3g)", types: &["artifact"] },
+    Family { name: "EnchantmentType", variant: "Enchantment", doc: "Enchantment subtypes (205.3h)", types: &["enchantment"] },
+    Family { name: "LandType", variant: "Land", doc: "Land subtypes (205.3i)", types: &["land"] },
+    Family { name: "PlaneswalkerType", variant: "Planeswalker", doc: "Planeswalker subtypes (205.3j)", types: &["planeswalker"] },
+    Family { name: "SpellType", variant: "Spell", doc: "Instant and sorcery subtypes (205.3k)", types: &["instant", "sorcery"] },
+    Family { name: "CreatureType", variant: "Creature", doc: "Creature and kindred subtypes (205.3m)", types: &["creature", "kindred", "tribal"] },
+    Family { name: "PlanarType", variant: "Plane", doc: "Plane types (205.3n)", types: &["plane"] },
+    Family { name: "BattleType", variant: "Battle", doc: "Battle subtypes (205.3q)", types: &["battle"] },
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", CATALOG);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let catalog = fs::read_to_string(CATALOG).expect("failed to read type catalog");
+    let catalog: Value = serde_json::from_str(&catalog).expect("invalid type catalog");
+    let data = catalog["data"].as_object().expect("catalog has no data");
+
+    let mut out = String::new();
+
+    let types: Vec<String> = data.keys().map(|key| capitalize(key)).collect();
+    writeln!(out, "closed_type_enum! {{").unwrap();
+    writeln!(out, "    /// Types (205.2a)").unwrap();
+    writeln!(out, "    Type {{").unwrap();
+    for type_ in &types {
+        writeln!(out, "        {},", type_).unwrap();
+    }
+    writeln!(out, "    }}\n}}\n").unwrap();
+
+    let supertypes: BTreeSet<&str> = data
+        .values()
+        .flat_map(|entry| strings(&entry["superTypes"]))
+        .collect();
+    writeln!(out, "closed_type_enum! {{").unwrap();
+    writeln!(out, "    /// Supertypes (205.4c)").unwrap();
+    writeln!(out, "    Supertype {{").unwrap();
+    for supertype in supertypes {
+        writeln!(out, "        {},", supertype).unwrap();
+    }
+    writeln!(out, "    }}\n}}\n").unwrap();
+
+    for family in FAMILIES {
+        let names: BTreeSet<&str> = family
+            .types
+            .iter()
+            .filter_map(|key| data.get(*key))
+            .flat_map(|entry| strings(&entry["subTypes"]))
+            .collect();
+
+        writeln!(out, "subtype_family! {{").unwrap();
+        writeln!(out, "    /// {}", family.doc).unwrap();
+        writeln!(out, "    {}, {} {{", family.name, family.variant).unwrap();
+        for name in names {
+            let ident = identifier(name);
+            if ident == name {
+                writeln!(out, "        {},", ident).unwrap();
+            } else {
+                writeln!(out, "        {} => {:?},", ident, name).unwrap();
+            }
+        }
+        writeln!(out, "    }}\n}}\n").unwrap();
+    }
+
+    writeln!(out, "subtypes! {{").unwrap();
+    for family in FAMILIES {
+        let valid_for: Vec<String> = family
+            .types
+            .iter()
+            .filter(|key| data.contains_key(**key))
+            .map(|key| capitalize(key))
+            .collect();
+        writeln!(
+            out,
+            "    {}({}) => [{}],",
+            family.variant,
+            family.name,
+            valid_for.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("types.rs");
+    fs::write(path, out).expect("failed to write generated types");
+}
+
+fn strings(value: &Value) -> impl Iterator<Item = &str> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The variant name for a subtype: `Power-Plant` becomes `PowerPlant` and
+/// `Bolas's Meditation Realm` becomes `BolassMeditationRealm`.
+fn identifier(name: &str) -> String {
+    name.split([' ', '-'])
+        .map(|word| {
+            capitalize(
+                &word
+                    .chars()
+                    .filter(char::is_ascii_alphanumeric)
+                    .collect::<String>(),
+            )
+        })
+        .collect()
+}
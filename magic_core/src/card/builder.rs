@@ -0,0 +1,295 @@
+use super::{CardData, LoyaltyValue, PtValue, TypeLine, TypeLineViolation};
+use crate::mana::ManaCost;
+use crate::type_::{ArtifactType, Subtype, Type};
+use crate::ColorIdentity;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Builds a `CardData`, checking that the result could be printed.
+#[derive(Debug, Clone)]
+pub struct CardDataBuilder {
+    card_data: CardData,
+}
+
+impl CardDataBuilder {
+    pub fn new(name: impl Into<String>) -> CardDataBuilder {
+        CardDataBuilder {
+            card_data: CardData {
+                name: name.into(),
+                mana_cost: ManaCost::new(),
+                color_indicator: None,
+                type_line: TypeLine::new(),
+                power_toughness: None,
+                loyalty: None,
+                text: String::new(),
+            },
+        }
+    }
+
+    pub fn mana_cost(mut self, mana_cost: ManaCost) -> Self {
+        self.card_data.mana_cost = mana_cost;
+        self
+    }
+
+    pub fn color_indicator(mut self, colors: ColorIdentity) -> Self {
+        self.card_data.color_indicator = Some(colors);
+        self
+    }
+
+    pub fn type_line(mut self, type_line: TypeLine) -> Self {
+        self.card_data.type_line = type_line;
+        self
+    }
+
+    pub fn power_toughness(
+        mut self,
+        power: impl Into<PtValue>,
+        toughness: impl Into<PtValue>,
+    ) -> Self {
+        self.card_data.power_toughness = Some((power.into(), toughness.into()));
+        self
+    }
+
+    pub fn loyalty(mut self, loyalty: impl Into<LoyaltyValue>) -> Self {
+        self.card_data.loyalty = Some(loyalty.into());
+        self
+    }
+
+    /// The rules text, including any reminder text.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.card_data.text = text.into();
+        self
+    }
+
+    pub fn build(self) -> Result<CardData, CardDataError> {
+        let card_data = self.card_data;
+        let type_line = &card_data.type_line;
+
+        if card_data.name.trim().is_empty() {
+            return Err(CardDataError::EmptyName);
+        }
+
+        let violations = type_line.validate();
+        if !violations.is_empty() {
+            return Err(CardDataError::InvalidTypeLine(violations));
+        }
+
+        let vehicle = Subtype::Artifact(ArtifactType::Vehicle);
+        if card_data.power_toughness.is_some()
+            && !type_line.has_type(Type::Creature)
+            && !type_line.has_subtype(vehicle)
+        {
+            return Err(CardDataError::UnexpectedPowerToughness);
+        }
+
+        if card_data.loyalty.is_some() && !type_line.has_type(Type::Planeswalker) {
+            return Err(CardDataError::UnexpectedLoyalty);
+        }
+
+        Ok(card_data)
+    }
+}
+
+/// Why a `CardDataBuilder` couldn't build a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardDataError {
+    EmptyName,
+    InvalidTypeLine(Vec<TypeLineViolation>),
+    /// Power and toughness on something that isn't a creature or a vehicle
+    /// (208.1).
+    UnexpectedPowerToughness,
+    /// Only one of power and toughness.
+    IncompletePowerToughness,
+    /// Loyalty on something that isn't a planeswalker (209.1).
+    UnexpectedLoyalty,
+}
+
+impl fmt::Display for CardDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardDataError::EmptyName => write!(f, "the card has no name"),
+            CardDataError::InvalidTypeLine(violations) => {
+                write!(f, "invalid type line: ")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    write!(f, "{}{}", if i > 0 { "; " } else { "" }, violation)?;
+                }
+                Ok(())
+            }
+            CardDataError::UnexpectedPowerToughness => {
+                write!(f, "only creatures and vehicles have power and toughness")
+            }
+            CardDataError::IncompletePowerToughness => {
+                write!(f, "power and toughness must be given together")
+            }
+            CardDataError::UnexpectedLoyalty => write!(f, "only planeswalkers have loyalty"),
+        }
+    }
+}
+
+impl std::error::Error for CardDataError {}
+
+/// The JSON form of `CardData`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct CardDataRepr {
+    name: String,
+    #[serde(default, skip_serializing_if = "ManaCost::is_empty")]
+    mana_cost: ManaCost,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color_indicator: Option<ColorIdentity>,
+    type_line: TypeLine,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    power: Option<PtValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    toughness: Option<PtValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    loyalty: Option<LoyaltyValue>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+}
+
+impl From<CardData> for CardDataRepr {
+    fn from(card_data: CardData) -> CardDataRepr {
+        let (power, toughness) = match card_data.power_toughness {
+            Some((power, toughness)) => (Some(power), Some(toughness)),
+            None => (None, None),
+        };
+
+        CardDataRepr {
+            name: card_data.name,
+            mana_cost: card_data.mana_cost,
+            color_indicator: card_data.color_indicator,
+            type_line: card_data.type_line,
+            power,
+            toughness,
+            loyalty: card_data.loyalty,
+            text: card_data.text,
+        }
+    }
+}
+
+impl TryFrom<CardDataRepr> for CardData {
+    type Error = CardDataError;
+
+    fn try_from(repr: CardDataRepr) -> Result<CardData, CardDataError> {
+        let mut builder = CardDataBuilder::new(repr.name)
+            .mana_cost(repr.mana_cost)
+            .type_line(repr.type_line)
+            .text(repr.text);
+
+        if let Some(colors) = repr.color_indicator {
+            builder = builder.color_indicator(colors);
+        }
+
+        match (repr.power, repr.toughness) {
+            (Some(power), Some(toughness)) => builder = builder.power_toughness(power, toughness),
+            (None, None) => {}
+            _ => return Err(CardDataError::IncompletePowerToughness),
+        }
+
+        if let Some(loyalty) = repr.loyalty {
+            builder = builder.loyalty(loyalty);
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::traits::Named;
+    use crate::Color;
+
+    fn goblin() -> CardData {
+        CardData::builder("Goblin Guide")
+            .mana_cost("{R}".parse().unwrap())
+            .type_line(type_line!(Creature; Goblin Scout))
+            .power_toughness(2, 2)
+            .text("Haste")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn build_and_read() {
+        let card = goblin();
+
+        assert_eq!(card.name().unwrap(), "Goblin Guide");
+        assert_eq!(card.mana_cost().to_string(), "{R}");
+        assert_eq!(card.type_line(), &type_line!(Creature; Goblin Scout));
+        assert_eq!(card.power(), Some(&PtValue::Number(2)));
+        assert_eq!(card.toughness(), Some(&PtValue::Number(2)));
+        assert_eq!(card.loyalty(), None);
+        assert_eq!(card.color_indicator(), None);
+        assert_eq!(card.text(), "Haste");
+    }
+
+    #[test]
+    fn build_errors() {
+        let error = CardData::builder("Shock")
+            .type_line(type_line!(Instant))
+            .power_toughness(2, 2)
+            .build();
+        assert_eq!(error, Err(CardDataError::UnexpectedPowerToughness));
+
+        let vehicle = CardData::builder("Smuggler's Copter")
+            .type_line(type_line!(Artifact; Vehicle))
+            .power_toughness(3, 3)
+            .build();
+        assert!(vehicle.is_ok());
+
+        let error = CardData::builder("Karn")
+            .type_line(type_line!(Legendary; Creature; Karn))
+            .loyalty(5)
+            .build();
+        assert!(matches!(error, Err(CardDataError::InvalidTypeLine(_))));
+
+        let error = CardData::builder("Grizzly Bears")
+            .type_line(type_line!(Creature; Bear))
+            .loyalty(5)
+            .build();
+        assert_eq!(error, Err(CardDataError::UnexpectedLoyalty));
+
+        let error = CardData::builder(" ").type_line(type_line!(Land)).build();
+        assert_eq!(error, Err(CardDataError::EmptyName));
+    }
+
+    #[test]
+    fn json() {
+        let card = goblin();
+        let json = serde_json::to_string(&card).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"name":"Goblin Guide","mana_cost":"{R}","type_line":"Creature — Goblin Scout","power":"2","toughness":"2","text":"Haste"}"#
+        );
+        assert_eq!(serde_json::from_str::<CardData>(&json).unwrap(), card);
+
+        let invalid = r#"{"name":"Shock","type_line":"Instant","power":"2"}"#;
+        assert!(serde_json::from_str::<CardData>(invalid).is_err());
+
+        let card = Card::DoubleFaced {
+            front: CardData::builder("Delver of Secrets")
+                .mana_cost("{U}".parse().unwrap())
+                .type_line(type_line!(Creature; Human Wizard))
+                .power_toughness(1, 1)
+                .build()
+                .unwrap(),
+            back: CardData::builder("Insectile Aberration")
+                .color_indicator(Color::Blue.into())
+                .type_line(type_line!(Creature; Human Insect))
+                .power_toughness(3, 2)
+                .build()
+                .unwrap(),
+            modal: false,
+        };
+
+        let json = serde_json::to_value(&card).unwrap();
+        assert_eq!(json["layout"], "double_faced");
+        assert_eq!(json["back"]["color_indicator"], serde_json::json!(["U"]));
+        assert_eq!(serde_json::from_value::<Card>(json).unwrap(), card);
+    }
+}
@@ -0,0 +1,188 @@
+use super::{Card, CardData, ParseMode, ParseOptions, TypeLine, TypeLineReadError};
+use crate::macros::impl_serialize_with_tostring;
+use serde::de::{self, Deserialize, Deserializer};
+use std::{fmt, str::FromStr};
+
+const SEPARATOR: &str = "//";
+
+/// The type lines of every face of a card, as Scryfall and MTGJSON print
+/// them: `Creature — Human Wizard // Creature — Human Insect`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FaceTypeLines {
+    faces: Vec<TypeLine>,
+}
+
+impl FaceTypeLines {
+    pub fn new(faces: Vec<TypeLine>) -> Self {
+        FaceTypeLines { faces }
+    }
+
+    /// The type line of each face, in printed order.
+    pub fn faces(&self) -> &[TypeLine] {
+        &self.faces
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    pub fn parse(s: &str, mode: ParseMode) -> Result<FaceTypeLines, TypeLineReadError> {
+        FaceTypeLines::parse_with(
+            s,
+            ParseOptions {
+                mode,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Parses each `//`-separated part as a type line. Error spans are
+    /// relative to the whole string.
+    pub fn parse_with(s: &str, options: ParseOptions) -> Result<FaceTypeLines, TypeLineReadError> {
+        let mut faces = vec![];
+        let mut start = 0;
+
+        for end in s
+            .match_indices(SEPARATOR)
+            .map(|(i, _)| i)
+            .chain(Some(s.len()))
+        {
+            let face = TypeLine::parse_with(&s[start..end], options)
+                .map_err(|error| error.offset_by(start))?;
+            faces.push(face);
+            start = end + SEPARATOR.len();
+        }
+
+        Ok(FaceTypeLines { faces })
+    }
+
+    /// Pairs each face of `card` with its type line, or `None` if `card`
+    /// doesn't have one face per type line.
+    pub fn pair_with<'a>(&'a self, card: &'a Card) -> Option<Vec<(&'a CardData, &'a TypeLine)>> {
+        let faces: Vec<_> = card.faces().collect();
+        if faces.len() != self.faces.len() {
+            return None;
+        }
+
+        Some(faces.into_iter().zip(self.faces.iter()).collect())
+    }
+}
+
+impl From<&Card> for FaceTypeLines {
+    fn from(card: &Card) -> FaceTypeLines {
+        FaceTypeLines::new(card.faces().map(|face| face.type_line.clone()).collect())
+    }
+}
+
+impl From<TypeLine> for FaceTypeLines {
+    fn from(type_line: TypeLine) -> FaceTypeLines {
+        FaceTypeLines::new(vec![type_line])
+    }
+}
+
+impl fmt::Display for FaceTypeLines {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, face) in self.faces.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", SEPARATOR)?;
+            }
+            write!(f, "{}", face)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses type lines in `ParseMode::Strict`.
+impl FromStr for FaceTypeLines {
+    type Err = TypeLineReadError;
+
+    fn from_str(s: &str) -> Result<FaceTypeLines, TypeLineReadError> {
+        FaceTypeLines::parse(s, ParseMode::Strict)
+    }
+}
+
+/// Like `TypeLine`, these are deserialized in `ParseMode::Lenient`.
+impl<'de> Deserialize<'de> for FaceTypeLines {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FaceTypeLines, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        FaceTypeLines::parse(&s, ParseMode::Lenient).map_err(de::Error::custom)
+    }
+}
+
+impl_serialize_with_tostring!(FaceTypeLines);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn face_type_lines() {
+        #[rustfmt::skip]
+        let pairs = vec![
+            (
+                "Creature \u{2014} Human Wizard // Creature \u{2014} Human Insect",
+                vec![type_line!(Creature; Human Wizard), type_line!(Creature; Human Insect)],
+            ),
+            (
+                "Creature \u{2014} Human Knight // Sorcery \u{2014} Adventure",
+                vec![type_line!(Creature; Human Knight), type_line!(Sorcery; Adventure)],
+            ),
+            (
+                "Instant // Instant",
+                vec![type_line!(Instant), type_line!(Instant)],
+            ),
+            (
+                "Legendary Land",
+                vec![type_line!(Legendary; Land;)],
+            ),
+        ];
+
+        for (string, faces) in pairs {
+            let lines: FaceTypeLines = string.parse().unwrap();
+            assert_eq!(lines.faces(), &faces[..]);
+            assert_eq!(lines.to_string(), string);
+
+            let json = serde_json::to_string(&lines).unwrap();
+            assert_eq!(serde_json::from_str::<FaceTypeLines>(&json).unwrap(), lines);
+        }
+    }
+
+    #[test]
+    fn errors_point_into_the_whole_line() {
+        let error = "Instant // Sorcry".parse::<FaceTypeLines>().unwrap_err();
+        assert_eq!(error.word(), "Sorcry");
+        assert_eq!(error.span(), 11..17);
+    }
+
+    #[test]
+    fn pair_with_card_faces() {
+        let card = Card::Split {
+            left: CardData {
+                name: "Fire".to_string(),
+                type_line: type_line!(Instant),
+                ..Default::default()
+            },
+            right: CardData {
+                name: "Ice".to_string(),
+                type_line: type_line!(Instant),
+                ..Default::default()
+            },
+            fuse: false,
+        };
+
+        let lines = FaceTypeLines::from(&card);
+        assert_eq!(lines.to_string(), "Instant // Instant");
+
+        let pairs = lines.pair_with(&card).unwrap();
+        assert_eq!(pairs[1].0.name, "Ice");
+        assert_eq!(pairs[1].1, &type_line!(Instant));
+
+        let single = FaceTypeLines::from(type_line!(Instant));
+        assert!(single.pair_with(&card).is_none());
+    }
+}
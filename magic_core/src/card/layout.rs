@@ -0,0 +1,203 @@
+use super::{Card, Face};
+use crate::type_::Type;
+use crate::zone::Zone;
+use serde::{Deserialize, Serialize};
+
+/// How a card's faces are printed, using Scryfall's layout names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    Normal,
+    Split,
+    Aftermath,
+    Room,
+    Flip,
+    Transform,
+    ModalDfc,
+    Battle,
+    Meld,
+    Adventure,
+    Prototype,
+    Leveler,
+    Saga,
+    Class,
+    Case,
+    Planar,
+    Scheme,
+    Vanguard,
+    Token,
+    Emblem,
+    Augment,
+    Host,
+}
+
+/// The layouts of `Card::Special`: cards with one face but a frame or
+/// play pattern of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialLayout {
+    Leveler,
+    Saga,
+    Class,
+    Case,
+    Planar,
+    Scheme,
+    Vanguard,
+    Token,
+    Emblem,
+    Augment,
+    Host,
+}
+
+impl From<SpecialLayout> for Layout {
+    fn from(layout: SpecialLayout) -> Layout {
+        match layout {
+            SpecialLayout::Leveler => Layout::Leveler,
+            SpecialLayout::Saga => Layout::Saga,
+            SpecialLayout::Class => Layout::Class,
+            SpecialLayout::Case => Layout::Case,
+            SpecialLayout::Planar => Layout::Planar,
+            SpecialLayout::Scheme => Layout::Scheme,
+            SpecialLayout::Vanguard => Layout::Vanguard,
+            SpecialLayout::Token => Layout::Token,
+            SpecialLayout::Emblem => Layout::Emblem,
+            SpecialLayout::Augment => Layout::Augment,
+            SpecialLayout::Host => Layout::Host,
+        }
+    }
+}
+
+impl Card {
+    pub fn layout(&self) -> Layout {
+        match self {
+            Card::Normal(_) => Layout::Normal,
+            Card::Special { kind, .. } => (*kind).into(),
+            Card::Split { .. } => Layout::Split,
+            Card::Aftermath { .. } => Layout::Aftermath,
+            Card::Room { .. } => Layout::Room,
+            Card::Flip { .. } => Layout::Flip,
+            Card::DoubleFaced { modal: true, .. } => Layout::ModalDfc,
+            Card::DoubleFaced { front, .. } if front.type_line.has_type(Type::Battle) => {
+                Layout::Battle
+            }
+            Card::DoubleFaced { .. } => Layout::Transform,
+            Card::Meld { .. } => Layout::Meld,
+            Card::Adventure { .. } => Layout::Adventure,
+            Card::Prototype { .. } => Layout::Prototype,
+        }
+    }
+
+    /// The faces of this card that can be cast from `zone`, ignoring
+    /// timing and permission. Only one face of a transforming, flip or meld
+    /// card can be cast (712.11a), the second half of an aftermath card
+    /// can only be cast from a graveyard and the first half can't
+    /// (702.127a), and tokens, emblems and nontraditional cards are never
+    /// cast.
+    pub fn castable_faces(&self, zone: Zone) -> Vec<Face> {
+        use self::Face::*;
+
+        match self {
+            Card::Special { kind, .. } => match kind {
+                SpecialLayout::Planar
+                | SpecialLayout::Scheme
+                | SpecialLayout::Vanguard
+                | SpecialLayout::Token
+                | SpecialLayout::Emblem
+                | SpecialLayout::Augment => vec![],
+                _ => vec![Front],
+            },
+            Card::Aftermath { .. } if zone == Zone::Graveyard => vec![Back],
+            Card::Aftermath { .. } => vec![Front],
+            Card::Split { .. }
+            | Card::Room { .. }
+            | Card::DoubleFaced { modal: true, .. }
+            | Card::Adventure { .. }
+            | Card::Prototype { .. } => vec![Front, Back],
+            Card::Normal(_)
+            | Card::Flip { .. }
+            | Card::DoubleFaced { modal: false, .. }
+            | Card::Meld { .. } => vec![Front],
+        }
+    }
+
+    /// The name of the card this melds with, for one card of a meld pair.
+    pub fn melds_with(&self) -> Option<&str> {
+        match self {
+            Card::Meld { partner, .. } => Some(partner),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardData;
+
+    fn face(name: &str, type_line: crate::card::TypeLine) -> CardData {
+        CardData {
+            name: name.to_string(),
+            type_line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn layouts() {
+        let battle = Card::DoubleFaced {
+            front: face("Invasion of Zendikar", type_line!(Battle; Siege)),
+            back: face("Awakened Skyclave", type_line!(Creature; Elemental)),
+            modal: false,
+        };
+        assert_eq!(battle.layout(), Layout::Battle);
+
+        let saga = Card::Special {
+            kind: SpecialLayout::Saga,
+            face: face("The Eldest Reborn", type_line!(Enchantment; Saga)),
+        };
+        assert_eq!(saga.layout(), Layout::Saga);
+        assert_eq!(
+            serde_json::to_value(Layout::ModalDfc).unwrap(),
+            serde_json::json!("modal_dfc")
+        );
+    }
+
+    #[test]
+    fn castable_faces() {
+        let card = Card::Aftermath {
+            top: face("Cut", type_line!(Sorcery)),
+            bottom: face("Ribbons", type_line!(Sorcery)),
+        };
+        assert_eq!(card.castable_faces(Zone::Hand), vec![Face::Front]);
+        assert_eq!(card.castable_faces(Zone::Graveyard), vec![Face::Back]);
+
+        let card = Card::Meld {
+            front: face(
+                "Bruna, the Fading Light",
+                type_line!(Legendary; Creature; Angel Horror),
+            ),
+            melded: face(
+                "Brisela, Voice of Nightmares",
+                type_line!(Legendary; Creature; Eldrazi Angel),
+            ),
+            partner: "Gisela, the Broken Blade".to_string(),
+        };
+        assert_eq!(card.melds_with(), Some("Gisela, the Broken Blade"));
+        assert_eq!(card.castable_faces(Zone::Hand), vec![Face::Front]);
+
+        let card = Card::Adventure {
+            creature: face("Bonecrusher Giant", type_line!(Creature; Giant)),
+            adventure: face("Stomp", type_line!(Instant; Adventure)),
+        };
+        assert_eq!(
+            card.castable_faces(Zone::Hand),
+            vec![Face::Front, Face::Back]
+        );
+
+        let plane = Card::Special {
+            kind: SpecialLayout::Planar,
+            face: face("Tazeem", type_line!(Plane; Zendikar)),
+        };
+        assert!(plane.castable_faces(Zone::Command).is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+use super::{Card, CardData};
+use crate::mana::{ConvertedManaCost, ManaCost, ManaSymbol};
+
+/// One face of a multi-face card. For split cards `Front` is the left
+/// half, and for flip cards `Back` is the flipped half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Face {
+    #[default]
+    Front,
+    Back,
+}
+
+/// The choices made while casting a spell that affect its mana value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CastChoices {
+    /// The value chosen for X (107.3).
+    pub x: u32,
+    /// The face or half that was cast.
+    pub face: Face,
+    /// Whether both halves of a split card were cast with fuse (702.102).
+    pub fused: bool,
+}
+
+/// Where an object is when its mana value is determined (202.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManaValueContext {
+    /// A card in any zone other than the stack, or a permanent, with the
+    /// given face up. X is 0 (202.3e). A meld card's back face is only up
+    /// as part of a melded permanent, which uses `Melded` instead.
+    InZone(Face),
+    /// A spell on the stack, using the choices made while casting it.
+    OnStack(CastChoices),
+    /// A copy of a spell, which keeps the choices made for the original
+    /// (707.10).
+    CopyOfSpell(CastChoices),
+    /// A copy of a permanent with the given face up. X is 0.
+    CopyOfPermanent(Face),
+    /// A melded permanent, given the mana value of the front face of the
+    /// other meld card it is made from (712.8h).
+    Melded { partner: usize },
+}
+
+impl Default for ManaValueContext {
+    fn default() -> ManaValueContext {
+        ManaValueContext::InZone(Face::Front)
+    }
+}
+
+/// The mana value of an object, following rule 202.3.
+pub trait ManaValue {
+    fn mana_value(&self, context: ManaValueContext) -> usize;
+}
+
+impl ManaValue for ManaCost {
+    fn mana_value(&self, context: ManaValueContext) -> usize {
+        let x = match context {
+            ManaValueContext::InZone(_)
+            | ManaValueContext::CopyOfPermanent(_)
+            | ManaValueContext::Melded { .. } => 0,
+            ManaValueContext::OnStack(choices) | ManaValueContext::CopyOfSpell(choices) => {
+                choices.x
+            }
+        };
+
+        let variables = self
+            .symbols()
+            .iter()
+            .filter(|&&symbol| symbol == ManaSymbol::Variable)
+            .count();
+
+        self.converted_mana_cost() + variables * x as usize
+    }
+}
+
+impl ManaValue for CardData {
+    fn mana_value(&self, context: ManaValueContext) -> usize {
+        self.mana_cost.mana_value(context)
+    }
+}
+
+impl ManaValue for Card {
+    fn mana_value(&self, context: ManaValueContext) -> usize {
+        use self::ManaValueContext::*;
+
+        match self {
+            Card::Normal(card_data)
+            | Card::Special {
+                face: card_data, ..
+            } => card_data.mana_value(context),
+
+            // A split card not on the stack has the combined mana value of
+            // its halves. On the stack, only the cast half counts unless
+            // it was fused (709.4).
+            Card::Split { left, right, .. }
+            | Card::Aftermath {
+                top: left,
+                bottom: right,
+            }
+            | Card::Room { left, right } => match context {
+                InZone(_) | CopyOfPermanent(_) | Melded { .. } => {
+                    left.mana_value(context) + right.mana_value(context)
+                }
+                OnStack(choices) | CopyOfSpell(choices) => match (choices.fused, choices.face) {
+                    (true, _) => left.mana_value(context) + right.mana_value(context),
+                    (false, Face::Front) => left.mana_value(context),
+                    (false, Face::Back) => right.mana_value(context),
+                },
+            },
+
+            // Flipping doesn't change a card's mana cost (710.2).
+            Card::Flip { top, .. } => top.mana_value(context),
+
+            // The back face of a meld card has no mana cost. A melded
+            // permanent's mana value is the total of the mana values of the
+            // front faces of both meld cards, and a copy of it has mana
+            // value 0 (712.8h).
+            Card::Meld { front, .. } => match context {
+                Melded { partner } => front.mana_value(context) + partner,
+                CopyOfPermanent(Face::Back) => 0,
+                _ => front.mana_value(context),
+            },
+
+            // An adventure has only its alternative characteristics on
+            // the stack (715.3).
+            Card::Adventure {
+                creature: main,
+                adventure: alternative,
+            } => match context {
+                OnStack(CastChoices {
+                    face: Face::Back, ..
+                })
+                | CopyOfSpell(CastChoices {
+                    face: Face::Back, ..
+                }) => alternative.mana_value(context),
+                _ => main.mana_value(context),
+            },
+
+            // A prototyped spell keeps its prototype characteristics as a
+            // permanent, and so do copies of it (718.3b, 718.3c).
+            Card::Prototype {
+                card: main,
+                prototype: alternative,
+            } => match context {
+                InZone(Face::Back)
+                | CopyOfPermanent(Face::Back)
+                | OnStack(CastChoices {
+                    face: Face::Back, ..
+                })
+                | CopyOfSpell(CastChoices {
+                    face: Face::Back, ..
+                }) => alternative.mana_value(context),
+                _ => main.mana_value(context),
+            },
+
+            // A modal double-faced card uses the face that is up or was
+            // cast (712.8).
+            Card::DoubleFaced {
+                front,
+                back,
+                modal: true,
+            } => match context {
+                InZone(Face::Front) | CopyOfPermanent(Face::Front) | Melded { .. } => {
+                    front.mana_value(context)
+                }
+                InZone(Face::Back) | CopyOfPermanent(Face::Back) => back.mana_value(context),
+                OnStack(choices) | CopyOfSpell(choices) => match choices.face {
+                    Face::Front => front.mana_value(context),
+                    Face::Back => back.mana_value(context),
+                },
+            },
+
+            // A transforming double-faced card's back face uses the mana
+            // cost of its front face, except that a copy of a permanent
+            // with its back face up has mana value 0 (712.8).
+            Card::DoubleFaced {
+                front,
+                modal: false,
+                ..
+            } => match context {
+                CopyOfPermanent(Face::Back) => 0,
+                _ => front.mana_value(context),
+            },
+        }
+    }
+}
+
+impl ConvertedManaCost for Card {
+    fn converted_mana_cost(&self) -> usize {
+        self.mana_value(ManaValueContext::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(name: &str, mana_cost: &str) -> CardData {
+        CardData {
+            name: name.to_string(),
+            mana_cost: mana_cost.parse().unwrap(),
+            type_line: type_line!(Instant),
+            ..Default::default()
+        }
+    }
+
+    fn on_stack(x: u32, face: Face, fused: bool) -> ManaValueContext {
+        ManaValueContext::OnStack(CastChoices { x, face, fused })
+    }
+
+    #[test]
+    fn x_is_zero_except_on_the_stack() {
+        let card = Card::Normal(face("Fireball", "{X}{R}"));
+
+        assert_eq!(card.mana_value(ManaValueContext::default()), 1);
+        assert_eq!(card.mana_value(on_stack(4, Face::Front, false)), 5);
+        assert_eq!(card.converted_mana_cost(), 1);
+
+        let card = Card::Normal(face("Crackle with Power", "{X}{X}{X}{R}"));
+        assert_eq!(card.mana_value(on_stack(2, Face::Front, false)), 7);
+    }
+
+    #[test]
+    fn copies() {
+        let card = Card::Normal(face("Hangarback Walker", "{X}{X}"));
+        let choices = CastChoices {
+            x: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(card.mana_value(ManaValueContext::CopyOfSpell(choices)), 6);
+        assert_eq!(
+            card.mana_value(ManaValueContext::CopyOfPermanent(Face::Front)),
+            0
+        );
+    }
+
+    #[test]
+    fn split_cards() {
+        let card = Card::Split {
+            left: face("Fire", "{1}{R}"),
+            right: face("Ice", "{1}{U}"),
+            fuse: false,
+        };
+
+        assert_eq!(card.mana_value(ManaValueContext::InZone(Face::Front)), 4);
+        assert_eq!(card.mana_value(on_stack(0, Face::Front, false)), 2);
+        assert_eq!(card.mana_value(on_stack(0, Face::Back, false)), 2);
+
+        let card = Card::Split {
+            left: face("Turn", "{2}{U}"),
+            right: face("Burn", "{1}{R}"),
+            fuse: true,
+        };
+
+        assert_eq!(card.mana_value(on_stack(0, Face::Front, true)), 5);
+    }
+
+    #[test]
+    fn double_faced_cards() {
+        let transforming = Card::DoubleFaced {
+            front: face("Delver of Secrets", "{U}"),
+            back: face("Insectile Aberration", ""),
+            modal: false,
+        };
+
+        assert_eq!(
+            transforming.mana_value(ManaValueContext::InZone(Face::Back)),
+            1
+        );
+        let copy = ManaValueContext::CopyOfPermanent(Face::Back);
+        assert_eq!(transforming.mana_value(copy), 0);
+        let spell_copy = ManaValueContext::CopyOfSpell(CastChoices {
+            face: Face::Back,
+            ..Default::default()
+        });
+        assert_eq!(transforming.mana_value(spell_copy), 1);
+
+        let modal = Card::DoubleFaced {
+            front: face("Agadeem's Awakening", "{X}{B}{B}{B}"),
+            back: face("Agadeem, the Undercrypt", ""),
+            modal: true,
+        };
+
+        assert_eq!(modal.mana_value(ManaValueContext::InZone(Face::Front)), 3);
+        assert_eq!(modal.mana_value(ManaValueContext::InZone(Face::Back)), 0);
+        assert_eq!(modal.mana_value(on_stack(3, Face::Front, false)), 6);
+        assert_eq!(modal.mana_value(copy), 0);
+    }
+
+    #[test]
+    fn melded_permanents() {
+        let bruna = Card::Meld {
+            front: face("Bruna, the Fading Light", "{5}{W}{W}"),
+            melded: face("Brisela, Voice of Nightmares", ""),
+            partner: "Gisela, the Broken Blade".to_string(),
+        };
+        let gisela = face("Gisela, the Broken Blade", "{2}{W}{W}");
+
+        assert_eq!(bruna.mana_value(ManaValueContext::default()), 7);
+        let melded = ManaValueContext::Melded {
+            partner: gisela.mana_value(ManaValueContext::default()),
+        };
+        assert_eq!(bruna.mana_value(melded), 11);
+        assert_eq!(
+            bruna.mana_value(ManaValueContext::CopyOfPermanent(Face::Back)),
+            0
+        );
+    }
+
+    #[test]
+    fn adventures_and_prototypes() {
+        let card = Card::Adventure {
+            creature: face("Bonecrusher Giant", "{2}{R}"),
+            adventure: face("Stomp", "{1}{R}"),
+        };
+
+        assert_eq!(card.mana_value(ManaValueContext::default()), 3);
+        assert_eq!(card.mana_value(on_stack(0, Face::Back, false)), 2);
+
+        let card = Card::Prototype {
+            card: face("Phyrexian Fleshgorger", "{7}"),
+            prototype: face("Phyrexian Fleshgorger", "{1}{B}{B}"),
+        };
+
+        assert_eq!(card.mana_value(on_stack(0, Face::Front, false)), 7);
+        assert_eq!(card.mana_value(on_stack(0, Face::Back, false)), 3);
+        assert_eq!(card.mana_value(ManaValueContext::InZone(Face::Back)), 3);
+        assert_eq!(
+            card.mana_value(ManaValueContext::CopyOfPermanent(Face::Back)),
+            3
+        );
+        assert_eq!(card.mana_value(ManaValueContext::default()), 7);
+    }
+}
@@ -1,28 +1,143 @@
-use super::ColorIdentity;
+use super::{Color, ColorIdentity};
 use crate::mana::{ConvertedManaCost, ManaCost};
+use crate::symbol::{self, Segment};
 use crate::traits::Named;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 #[macro_use]
 mod type_line;
+mod builder;
+mod face_type_lines;
+mod layout;
+mod mana_value;
+mod value;
 
-pub use self::type_line::TypeLine;
+pub use self::builder::{CardDataBuilder, CardDataError};
+pub use self::face_type_lines::FaceTypeLines;
+pub use self::layout::{Layout, SpecialLayout};
+pub use self::mana_value::{CastChoices, Face, ManaValue, ManaValueContext};
+pub use self::type_line::{
+    ParseMode, ParseOptions, TypeLine, TypeLineReadError, TypeLineReadErrorKind, TypeLineSection,
+    TypeLineViolation,
+};
+pub use self::value::{KnownValues, LoyaltyValue, ParseValueError, PtValue, ValueContext};
 
-// TODO: Add a type that supports X loyalty
-type Loyalty = i32;
-
-// TODO: Add a type that supports * for power and/or toughness
-type Power = i32;
-type Toughness = i32;
-
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// The characteristics of a card or of one face of a card. Build one with
+/// `CardData::builder`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "builder::CardDataRepr", into = "builder::CardDataRepr")]
 pub struct CardData {
     name: String,
     mana_cost: ManaCost,
     color_indicator: Option<ColorIdentity>,
     type_line: TypeLine,
-    power_toughness: Option<(Power, Toughness)>,
-    loyalty: Option<Loyalty>,
+    power_toughness: Option<(PtValue, PtValue)>,
+    loyalty: Option<LoyaltyValue>,
+    text: String,
+}
+
+/// Empty, unvalidated data, so that tests can fill in only the fields they
+/// need. Other code goes through `CardData::builder`.
+#[cfg(test)]
+impl Default for CardData {
+    fn default() -> CardData {
+        CardData {
+            name: String::new(),
+            mana_cost: ManaCost::new(),
+            color_indicator: None,
+            type_line: TypeLine::new(),
+            power_toughness: None,
+            loyalty: None,
+            text: String::new(),
+        }
+    }
+}
+
+impl CardData {
+    pub fn builder(name: impl Into<String>) -> CardDataBuilder {
+        CardDataBuilder::new(name)
+    }
+
+    pub fn mana_cost(&self) -> &ManaCost {
+        &self.mana_cost
+    }
+
+    pub fn color_indicator(&self) -> Option<ColorIdentity> {
+        self.color_indicator
+    }
+
+    pub fn type_line(&self) -> &TypeLine {
+        &self.type_line
+    }
+
+    pub fn power_toughness(&self) -> Option<(&PtValue, &PtValue)> {
+        self.power_toughness.as_ref().map(|(p, t)| (p, t))
+    }
+
+    pub fn power(&self) -> Option<&PtValue> {
+        self.power_toughness.as_ref().map(|(p, _)| p)
+    }
+
+    pub fn toughness(&self) -> Option<&PtValue> {
+        self.power_toughness.as_ref().map(|(_, t)| t)
+    }
+
+    pub fn loyalty(&self) -> Option<&LoyaltyValue> {
+        self.loyalty.as_ref()
+    }
+
+    /// The rules text, including any reminder text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The colors of this card or face from its mana cost and color
+    /// indicator (202.2, 204).
+    pub fn colors(&self) -> ColorIdentity {
+        let indicator = self.color_indicator.unwrap_or_default();
+        self.mana_cost
+            .colors()
+            .colors()
+            .chain(indicator.colors())
+            .collect()
+    }
+
+    /// The devotion to `color` this face contributes as a permanent
+    /// (700.5).
+    pub fn devotion(&self, color: Color) -> usize {
+        self.mana_cost.devotion(color)
+    }
+
+    pub fn devotion_to(&self, colors: ColorIdentity) -> usize {
+        self.mana_cost.devotion_to(colors)
+    }
+
+    /// The color identity this face contributes: its colors plus the
+    /// colors of mana symbols in its rules text (903.4). Symbols in
+    /// reminder text don't count (903.4a).
+    pub fn color_identity(&self) -> ColorIdentity {
+        let mut depth = 0usize;
+        let mut identity = self.colors();
+
+        for segment in symbol::tokenize(&self.text) {
+            match segment {
+                Segment::Text(text) => {
+                    for c in text.chars() {
+                        match c {
+                            '(' => depth += 1,
+                            ')' => depth = depth.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                }
+                Segment::Symbol(symbol) if depth == 0 => identity |= symbol.colors(),
+                Segment::Symbol(_) => {}
+            }
+        }
+
+        identity
+    }
 }
 
 impl ConvertedManaCost for CardData {
@@ -32,44 +147,151 @@ impl ConvertedManaCost for CardData {
 }
 
 impl Named for CardData {
-    fn name(&self) -> Option<Cow<str>> {
+    fn name(&self) -> Option<Cow<'_, str>> {
         Some(Cow::Borrowed(&self.name))
     }
 }
 
+/// A card with its faces, structured by layout. Serialized with a `layout`
+/// tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "layout", rename_all = "snake_case")]
 pub enum Card {
     Normal(CardData),
+    /// A card with one face and a special frame, or one that isn't a
+    /// traditional Magic card.
+    Special {
+        kind: SpecialLayout,
+        face: CardData,
+    },
+    /// A split card (709), which may have fuse (702.102).
     Split {
         left: CardData,
         right: CardData,
         fuse: bool,
     },
+    /// A split card whose second half has aftermath, so it can only be
+    /// cast from a graveyard (702.127).
+    Aftermath {
+        top: CardData,
+        bottom: CardData,
+    },
+    /// A split card with two doors (709.5).
+    Room {
+        left: CardData,
+        right: CardData,
+    },
     Flip {
         top: CardData,
         bottom: CardData,
     },
+    /// A transforming double-faced card, including battles, or a modal
+    /// double-faced card (712).
     DoubleFaced {
         front: CardData,
         back: CardData,
+        modal: bool,
+    },
+    /// One card of a meld pair (712.4). Its back face is half of `melded`,
+    /// the permanent it forms with the card named `partner`.
+    Meld {
+        front: CardData,
+        melded: CardData,
+        partner: String,
     },
+    /// An adventurer card (715).
+    Adventure {
+        creature: CardData,
+        adventure: CardData,
+    },
+    /// A card with prototype, whose alternative characteristics are
+    /// `prototype` (718).
+    Prototype {
+        card: CardData,
+        prototype: CardData,
+    },
+}
+
+impl Card {
+    /// Every face of this card, in printed order.
+    pub fn faces(&self) -> impl Iterator<Item = &CardData> {
+        let faces = match self {
+            Card::Normal(card_data)
+            | Card::Special {
+                face: card_data, ..
+            } => vec![card_data],
+            Card::Split { left, right, .. } | Card::Room { left, right } => vec![left, right],
+            Card::Aftermath { top, bottom } | Card::Flip { top, bottom } => vec![top, bottom],
+            Card::DoubleFaced { front, back, .. } => vec![front, back],
+            Card::Meld { front, melded, .. } => vec![front, melded],
+            Card::Adventure {
+                creature,
+                adventure,
+            } => vec![creature, adventure],
+            Card::Prototype { card, prototype } => vec![card, prototype],
+        };
+
+        faces.into_iter()
+    }
+
+    /// The colors of this card in any zone other than the stack or the
+    /// battlefield (202.2). A split card has the colors of both halves
+    /// (709.4), while other cards use the face that is normally up or
+    /// their normal characteristics (710.1, 712.8a, 715.4, 718.4).
+    pub fn colors(&self) -> ColorIdentity {
+        match self {
+            Card::Split { left, right, .. } | Card::Room { left, right } => {
+                left.colors() | right.colors()
+            }
+            Card::Aftermath { top, bottom } => top.colors() | bottom.colors(),
+            _ => self.main_face().colors(),
+        }
+    }
+
+    /// The face whose characteristics a card has when it isn't a split
+    /// card and isn't transformed, flipped, melded or cast with an
+    /// alternative face.
+    pub(crate) fn main_face(&self) -> &CardData {
+        match self {
+            Card::Normal(card_data)
+            | Card::Special {
+                face: card_data, ..
+            } => card_data,
+            Card::Split { left, .. } | Card::Room { left, .. } => left,
+            Card::Aftermath { top, .. } | Card::Flip { top, .. } => top,
+            Card::DoubleFaced { front, .. } | Card::Meld { front, .. } => front,
+            Card::Adventure { creature, .. } => creature,
+            Card::Prototype { card, .. } => card,
+        }
+    }
+
+    /// The color identity of this card for the Commander variant, which
+    /// includes every face (903.4, 903.4d).
+    pub fn color_identity(&self) -> ColorIdentity {
+        self.faces()
+            .map(CardData::color_identity)
+            .fold(ColorIdentity::default(), |a, b| a | b)
+    }
 }
 
+/// Names follow the rules for a card outside the stack and battlefield: a
+/// split card has the names of both halves (709.4), and the other
+/// multi-face cards have only the name of their main face, like the front
+/// face of a double-faced card (712.8a).
 impl Named for Card {
-    fn name(&self) -> Option<Cow<str>> {
+    fn name(&self) -> Option<Cow<'_, str>> {
         match self {
-            Card::Normal(card_data) => card_data.name(),
-            Card::Split { .. } => None,
-            Card::Flip { .. } => None,
-            Card::DoubleFaced { .. } => None,
+            Card::Split { .. } | Card::Aftermath { .. } | Card::Room { .. } => None,
+            _ => self.main_face().name(),
         }
     }
 
-    fn names(&self) -> Vec<Cow<str>> {
+    fn names(&self) -> Vec<Cow<'_, str>> {
         match self {
-            Card::Normal(card_data) => vec![card_data.name().unwrap()],
-            Card::Split { left, right, .. } => vec![left.name().unwrap(), right.name().unwrap()],
-            Card::Flip { top, bottom } => vec![top.name().unwrap(), bottom.name().unwrap()],
-            Card::DoubleFaced { front, back } => vec![front.name().unwrap(), back.name().unwrap()],
+            Card::Split { .. } | Card::Aftermath { .. } | Card::Room { .. } => {
+                self.faces().flat_map(CardData::names).collect()
+            }
+            _ => self.main_face().names(),
         }
     }
 }
@@ -87,5 +309,113 @@ mod tests {
         });
 
         assert_eq!(card.name(), Some(Cow::Borrowed("Mountain")));
+        assert_eq!(card.display_name(), "Mountain");
+
+        let face = |name: &str| CardData {
+            name: name.to_string(),
+            type_line: type_line!(Instant),
+            ..Default::default()
+        };
+
+        let card = Card::Split {
+            left: face("Fire"),
+            right: face("Ice"),
+            fuse: false,
+        };
+        assert_eq!(card.name(), None);
+        assert_eq!(card.names(), vec!["Fire", "Ice"]);
+        assert_eq!(card.display_name(), "Fire // Ice");
+        assert!(card.match_name("Ice"));
+        assert!(card.match_name("fire//ice"));
+        assert!(!card.match_name("Fire // Ice // Fire"));
+
+        let card = Card::DoubleFaced {
+            front: face("Delver of Secrets"),
+            back: face("Insectile Aberration"),
+            modal: false,
+        };
+        assert_eq!(card.name(), Some(Cow::Borrowed("Delver of Secrets")));
+        assert_eq!(card.names(), vec!["Delver of Secrets"]);
+        assert!(!card.match_name("Insectile Aberration"));
+
+        let card = Card::Normal(face("Lim-Dûl's Vault"));
+        assert!(card.match_name("lim-dul's vault"));
+        assert!(card.match_name("Lim Dûls Vault"));
+        assert!(!card.match_name("Lim-Dûl"));
+    }
+
+    #[test]
+    fn face_colors_and_devotion() {
+        use crate::Color::*;
+
+        let card = Card::DoubleFaced {
+            front: CardData {
+                name: "Delver of Secrets".to_string(),
+                mana_cost: "{U}".parse().unwrap(),
+                type_line: type_line!(Creature; Human Wizard),
+                ..Default::default()
+            },
+            back: CardData {
+                name: "Insectile Aberration".to_string(),
+                color_indicator: Some([Blue].iter().cloned().collect()),
+                type_line: type_line!(Creature; Human Insect),
+                ..Default::default()
+            },
+            modal: false,
+        };
+
+        let colors: Vec<_> = card.faces().map(CardData::colors).collect();
+        assert_eq!(colors, vec![[Blue].iter().cloned().collect(); 2]);
+
+        let devotion: Vec<_> = card.faces().map(|face| face.devotion(Blue)).collect();
+        assert_eq!(devotion, vec![1, 0]);
+    }
+
+    #[test]
+    fn commander_color_identity() {
+        use crate::Color::*;
+
+        let card = Card::Normal(CardData {
+            name: "Alesha, Who Smiles at Death".to_string(),
+            mana_cost: "{2}{R}".parse().unwrap(),
+            type_line: type_line!(Legendary; Creature; Human Warrior),
+            text: "First strike\nWhenever Alesha attacks, you may pay {W/B}{W/B}. \
+                   If you do, return target creature card with power 2 or less \
+                   from your graveyard to the battlefield tapped and attacking."
+                .to_string(),
+            ..Default::default()
+        });
+
+        assert_eq!(card.colors(), Red.into());
+        assert_eq!(card.color_identity(), "RWB".parse().unwrap());
+
+        // Mana symbols in reminder text are ignored.
+        let card = Card::Normal(CardData {
+            name: "Wastes".to_string(),
+            type_line: type_line!(Basic; Land;),
+            text: "({T}: Add {C}.) Extort (Whenever you cast a spell, \
+                   you may pay {W/B}.)"
+                .to_string(),
+            ..Default::default()
+        });
+        assert!(card.color_identity().is_empty());
+
+        let card = Card::DoubleFaced {
+            front: CardData {
+                name: "Civilized Scholar".to_string(),
+                mana_cost: "{2}{U}".parse().unwrap(),
+                type_line: type_line!(Creature; Human Advisor),
+                ..Default::default()
+            },
+            back: CardData {
+                name: "Homicidal Brute".to_string(),
+                color_indicator: Some(Red.into()),
+                type_line: type_line!(Creature; Human Mutant),
+                ..Default::default()
+            },
+            modal: false,
+        };
+        assert_eq!(card.colors(), Blue.into());
+        assert_eq!(card.color_identity(), "UR".parse().unwrap());
     }
 }
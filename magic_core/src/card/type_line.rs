@@ -1,9 +1,11 @@
 use crate::{
-    macros::{impl_deserialize_with_fromstr, impl_serialize_with_tostring},
-    type_::{Subtype, Supertype, Type},
+    edit_distance::{edit_distance, suggestions},
+    type_::{CreatureType, Subtype, Supertype, Type},
 };
 use indexmap::IndexSet;
-use std::{fmt, str::FromStr};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::{fmt, ops::Range, str::FromStr};
 
 const EM_DASH: char = '\u{2014}';
 
@@ -12,6 +14,9 @@ pub struct TypeLine {
     supertypes: IndexSet<Supertype>,
     types: IndexSet<Type>,
     subtypes: IndexSet<Subtype>,
+    /// Whether this has every creature type, as with changeling (702.73a).
+    /// `Display` doesn't show this, but serialization keeps it.
+    all_creature_types: bool,
 }
 
 impl TypeLine {
@@ -30,6 +35,7 @@ impl TypeLine {
             supertypes: IndexSet::from_iter(supertypes),
             types: IndexSet::from_iter(types),
             subtypes: IndexSet::from_iter(subtypes),
+            all_creature_types: false,
         }
     }
 
@@ -53,8 +59,24 @@ impl TypeLine {
         self.types.contains(&type_)
     }
 
+    /// Whether this has `subtype`. With every creature type, this is true
+    /// of any creature type as long as this is a creature or kindred
+    /// (205.3d).
     pub fn has_subtype(&self, subtype: Subtype) -> bool {
         self.subtypes.contains(&subtype)
+            || (self.all_creature_types
+                && matches!(subtype, Subtype::Creature(_))
+                && self.types_iter().any(|ty| subtype.valid_for(ty)))
+    }
+
+    /// Gives this every creature type (205.3m, 702.73a). `subtypes_iter`
+    /// still only returns the creature types that are listed.
+    pub fn set_all_creature_types(&mut self) {
+        self.all_creature_types = true;
+    }
+
+    pub fn has_all_creature_types(&self) -> bool {
+        self.all_creature_types
     }
 
     pub fn remove_supertype(&mut self, supertype: Supertype) {
@@ -69,6 +91,54 @@ impl TypeLine {
         self.subtypes.remove(&subtype);
     }
 
+    /// Removes every subtype that goes with `type_`, as in "loses all
+    /// creature types". Removing creature types also removes every
+    /// creature type given by changeling.
+    pub fn remove_subtypes_of(&mut self, type_: Type) {
+        self.subtypes.retain(|subtype| !subtype.valid_for(type_));
+        if has_creature_types(type_) {
+            self.all_creature_types = false;
+        }
+    }
+
+    /// Replaces every card type with `types` (205.1a). Subtypes that no
+    /// longer correspond to one of the card types are removed too. Use
+    /// `add_type` for effects that add a type "in addition to its other
+    /// types" (205.1b).
+    pub fn set_types(&mut self, types: impl IntoIterator<Item = Type>) {
+        self.types = types.into_iter().collect();
+
+        let types = &self.types;
+        self.subtypes
+            .retain(|subtype| types.iter().any(|&ty| subtype.valid_for(ty)));
+
+        if !types.iter().any(|&ty| has_creature_types(ty)) {
+            self.all_creature_types = false;
+        }
+    }
+
+    /// Replaces the subtypes in the same families as `subtypes` with
+    /// `subtypes`, leaving the other families alone (205.1a). Setting a
+    /// land's subtype to a basic land type this way removes its old land
+    /// types, as Blood Moon does (305.7).
+    pub fn set_subtypes(&mut self, subtypes: impl IntoIterator<Item = Subtype>) {
+        use std::mem::discriminant;
+
+        let subtypes: Vec<_> = subtypes.into_iter().collect();
+        let replaced = |subtype: &Subtype| {
+            subtypes
+                .iter()
+                .any(|new| discriminant(new) == discriminant(subtype))
+        };
+
+        if subtypes.iter().any(|s| matches!(s, Subtype::Creature(_))) {
+            self.all_creature_types = false;
+        }
+
+        self.subtypes.retain(|subtype| !replaced(subtype));
+        self.subtypes.extend(subtypes);
+    }
+
     pub fn types_iter<'a>(&'a self) -> impl Iterator<Item = Type> + 'a {
         self.types.iter().cloned()
     }
@@ -82,13 +152,148 @@ impl TypeLine {
     }
 
     pub fn is_valid(&self) -> bool {
-        !self.types.is_empty() && {
-            self.subtypes_iter()
-                .all(|sub| self.types_iter().any(|ty| sub.valid_for(ty)))
+        self.validate().is_empty()
+    }
+
+    /// Every rule this type line breaks, in the order they were found.
+    pub fn validate(&self) -> Vec<TypeLineViolation> {
+        use self::TypeLineViolation::*;
+
+        let mut violations = vec![];
+        let has = |type_| self.has_type(type_);
+
+        if self.types.is_empty() {
+            violations.push(NoCardType);
+        }
+
+        for subtype in self.subtypes.iter() {
+            if !self.types_iter().any(|ty| subtype.valid_for(ty)) {
+                violations.push(InvalidSubtype(subtype.clone()));
+            }
         }
+
+        if self.has_supertype(Supertype::Basic) && !has(Type::Land) {
+            violations.push(BasicNonLand);
+        }
+
+        if self.has_supertype(Supertype::Ongoing) && !has(Type::Scheme) {
+            violations.push(OngoingNonScheme);
+        }
+
+        for &kindred in &[Type::Kindred, Type::Tribal] {
+            let others = [
+                Type::Artifact,
+                Type::Enchantment,
+                Type::Instant,
+                Type::Sorcery,
+            ];
+            if has(kindred) && !others.iter().any(|&ty| has(ty)) {
+                violations.push(KindredWithoutType(kindred));
+            }
+        }
+
+        for &exclusive in EXCLUSIVE_TYPES {
+            if has(exclusive) && self.types.len() > 1 {
+                violations.push(NotAlone(exclusive));
+            }
+        }
+
+        let planeswalker_types = self
+            .subtypes
+            .iter()
+            .filter(|subtype| matches!(subtype, Subtype::Planeswalker(_)))
+            .count();
+        if planeswalker_types > 1 {
+            violations.push(MultiplePlaneswalkerTypes(planeswalker_types));
+        }
+
+        violations
     }
 }
 
+/// Card types that never appear alongside another card type.
+const EXCLUSIVE_TYPES: &[Type] = &[
+    Type::Conspiracy,
+    Type::Dungeon,
+    Type::Phenomenon,
+    Type::Plane,
+    Type::Scheme,
+    Type::Vanguard,
+];
+
+/// A rule broken by a type line, as returned by `TypeLine::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeLineViolation {
+    /// There is no card type.
+    NoCardType,
+    /// A subtype that doesn't correspond to any of the card types.
+    InvalidSubtype(Subtype),
+    /// The basic supertype on something that isn't a land.
+    BasicNonLand,
+    /// The ongoing supertype on something that isn't a scheme.
+    OngoingNonScheme,
+    /// Kindred or tribal without an artifact, enchantment, instant or
+    /// sorcery type.
+    KindredWithoutType(Type),
+    /// A nontraditional card type combined with another card type.
+    NotAlone(Type),
+    /// More than one planeswalker type, with how many there are.
+    MultiplePlaneswalkerTypes(usize),
+}
+
+impl TypeLineViolation {
+    /// The comprehensive rules section that is broken.
+    pub fn rule(&self) -> &'static str {
+        use self::TypeLineViolation::*;
+
+        match self {
+            NoCardType => "205.2a",
+            InvalidSubtype(_) => "205.3d",
+            BasicNonLand => "205.4c",
+            OngoingNonScheme => "314.6",
+            KindredWithoutType(_) => "308.2",
+            NotAlone(Type::Conspiracy) => "315.1",
+            NotAlone(Type::Dungeon) => "309.1",
+            NotAlone(Type::Phenomenon) => "312.1",
+            NotAlone(Type::Plane) => "311.1",
+            NotAlone(Type::Scheme) => "314.1",
+            NotAlone(Type::Vanguard) => "313.1",
+            NotAlone(_) => "205.2a",
+            MultiplePlaneswalkerTypes(_) => "205.3j",
+        }
+    }
+}
+
+impl fmt::Display for TypeLineViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::TypeLineViolation::*;
+
+        match self {
+            NoCardType => write!(f, "there is no card type"),
+            InvalidSubtype(subtype) => {
+                write!(f, "`{}` is not a subtype of any of the card types", subtype)
+            }
+            BasicNonLand => write!(f, "only lands can be basic"),
+            OngoingNonScheme => write!(f, "only schemes can be ongoing"),
+            KindredWithoutType(type_) => write!(
+                f,
+                "{} needs an artifact, enchantment, instant or sorcery type",
+                type_
+            ),
+            NotAlone(type_) => write!(f, "{} can't have another card type", type_),
+            MultiplePlaneswalkerTypes(n) => {
+                write!(f, "there are {} planeswalker types instead of one", n)
+            }
+        }?;
+        write!(f, " ({})", self.rule())
+    }
+}
+
+/// Whether objects with `type_` can have creature types (205.3m).
+fn has_creature_types(type_: Type) -> bool {
+    Subtype::Creature(CreatureType::Other(String::new())).valid_for(type_)
+}
+
 impl fmt::Display for TypeLine {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for supertype in self.supertypes.iter() {
@@ -99,7 +304,7 @@ impl fmt::Display for TypeLine {
             write!(f, "{}{}", if i > 0 { " " } else { "" }, type_)?;
         }
 
-        if self.subtypes.len() > 0 {
+        if !self.subtypes.is_empty() {
             write!(f, " {}", EM_DASH)?;
         }
         for subtype in self.subtypes.iter() {
@@ -110,98 +315,394 @@ impl fmt::Display for TypeLine {
     }
 }
 
+/// Which part of a type line a word was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeLineSection {
+    Supertypes,
+    Types,
+    Subtypes,
+}
+
+impl fmt::Display for TypeLineSection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            TypeLineSection::Supertypes => "supertype",
+            TypeLineSection::Types => "card type",
+            TypeLineSection::Subtypes => "subtype",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeLineReadErrorKind {
+    /// A word that isn't a known type of the section it is in.
+    UnknownWord,
+    /// A hyphen, double hyphen or en dash used in place of an em dash.
+    NotAnEmDash,
+    /// A second dash.
+    UnexpectedDash,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TypeLineReadError {
-    NotAnEmDash(String),
-    ExtraParts(String),
+pub struct TypeLineReadError {
+    kind: TypeLineReadErrorKind,
+    word: String,
+    span: Range<usize>,
+    section: TypeLineSection,
+    suggestions: Vec<String>,
+}
+
+impl TypeLineReadError {
+    pub fn kind(&self) -> TypeLineReadErrorKind {
+        self.kind
+    }
+
+    /// The word that couldn't be read.
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// The byte range of the word in the parsed string.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    pub fn section(&self) -> TypeLineSection {
+        self.section
+    }
+
+    /// Known names close to the word, closest first.
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    /// This error with its span moved `offset` bytes later, for a type line
+    /// read from the middle of a longer string.
+    pub(super) fn offset_by(mut self, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
 }
 
 impl fmt::Display for TypeLineReadError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Debug::fmt(self, f)
+        let span = &self.span;
+        match self.kind {
+            TypeLineReadErrorKind::UnknownWord => write!(
+                f,
+                "unknown {} `{}` at bytes {}..{}",
+                self.section, self.word, span.start, span.end
+            ),
+            TypeLineReadErrorKind::NotAnEmDash => write!(
+                f,
+                "`{}` at bytes {}..{} is not an em dash",
+                self.word, span.start, span.end
+            ),
+            TypeLineReadErrorKind::UnexpectedDash => write!(
+                f,
+                "unexpected `{}` at bytes {}..{}",
+                self.word, span.start, span.end
+            ),
+        }?;
+
+        if let Some((last, rest)) = self.suggestions.split_last() {
+            write!(f, "; did you mean ")?;
+            for suggestion in rest {
+                write!(f, "`{}`, ", suggestion)?;
+            }
+            if !rest.is_empty() {
+                write!(f, "or ")?;
+            }
+            write!(f, "`{}`?", last)?;
+        }
+
+        Ok(())
     }
 }
 
-impl FromStr for TypeLine {
-    type Err = TypeLineReadError;
+impl std::error::Error for TypeLineReadError {}
 
-    fn from_str(s: &str) -> Result<TypeLine, TypeLineReadError> {
-        let mut halves = s.split(EM_DASH);
+/// How `TypeLine::parse` treats words it doesn't recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Reject any card type or subtype this crate doesn't know. Useful for
+    /// catching typos in hand-written cards.
+    #[default]
+    Strict,
+    /// Accept unknown subtypes as `Other` in the family that matches the
+    /// card types, skip unknown words before the dash and accept any dash.
+    /// Never fails, so that cards printed after this crate was written can
+    /// still be loaded.
+    Lenient,
+}
+
+/// Options for `TypeLine::parse_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// Accept `-` and `--` in place of an em dash, as typed by users.
+    pub ascii_dashes: bool,
+}
+
+/// Dashes that are mistaken for an em dash.
+const DASH_LOOKALIKES: &[&str] = &["-", "--", "\u{2013}"];
+
+/// Splits a type line into words and their byte ranges. An em dash is
+/// always a word of its own.
+fn words(s: &str) -> Vec<(Range<usize>, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+
+    for (i, c) in s.char_indices() {
+        if c.is_whitespace() || c == EM_DASH {
+            if let Some(start) = start.take() {
+                words.push((start..i, &s[start..i]));
+            }
+            if c == EM_DASH {
+                let end = i + c.len_utf8();
+                words.push((i..end, &s[i..end]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+
+    if let Some(start) = start {
+        words.push((start..s.len(), &s[start..]));
+    }
+
+    words
+}
+
+impl TypeLine {
+    pub fn parse(s: &str, mode: ParseMode) -> Result<TypeLine, TypeLineReadError> {
+        TypeLine::parse_with(
+            s,
+            ParseOptions {
+                mode,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn parse_with(s: &str, options: ParseOptions) -> Result<TypeLine, TypeLineReadError> {
+        let lenient = options.mode == ParseMode::Lenient;
+        let is_dash = |word: &str| {
+            word.starts_with(EM_DASH)
+                || ((lenient || options.ascii_dashes) && DASH_LOOKALIKES.contains(&word))
+        };
+
+        let words = words(s);
+        let (pre_dash, post_dash) = match words.iter().position(|(_, word)| is_dash(word)) {
+            Some(i) => (&words[..i], Some(&words[i + 1..])),
+            None => (&words[..], None),
+        };
 
         let mut type_line = TypeLine::new();
 
         // pre dash
         {
-            let mut parts = halves
-                .next()
-                .unwrap_or("")
-                .split_whitespace()
-                .filter(|s| !s.is_empty());
-
-            let mut current = parts.next();
-
-            while let Some(c) = current {
-                if let Ok(supertype) = c.parse::<Supertype>() {
-                    type_line.add_supertype(supertype);
-                    current = parts.next();
-                } else {
-                    break;
-                }
+            let mut parts = pre_dash.iter().peekable();
+
+            while let Some(supertype) = parts.peek().and_then(|(_, w)| w.parse().ok()) {
+                type_line.add_supertype(supertype);
+                parts.next();
             }
 
-            while let Some(c) = current {
-                if let Ok(type_) = c.parse::<Type>() {
+            for (span, word) in parts {
+                if let Ok(type_) = word.parse::<Type>() {
                     type_line.add_type(type_);
-                    current = parts.next();
-                } else {
-                    break;
+                } else if !lenient {
+                    let section = if type_line.types.is_empty() {
+                        TypeLineSection::Supertypes
+                    } else {
+                        TypeLineSection::Types
+                    };
+                    return Err(type_line.error(word, span, section));
                 }
             }
-
-            if current.is_some() {
-                return Err(TypeLineReadError::ExtraParts(current.unwrap().to_string()));
-            }
         }
 
         // post dash
-        {
-            if let Some(post_dash) = halves.next() {
-                let post_dash = post_dash.trim();
-                if let Ok(subtype) = post_dash.parse::<Subtype>() {
-                    type_line.add_subtype(subtype);
-                } else {
-                    let mut parts = post_dash.split_whitespace().filter(|s| !s.is_empty());
-
-                    let mut current = parts.next();
+        if let Some(post_dash) = post_dash.filter(|words| !words.is_empty()) {
+            let types: Vec<_> = type_line.types_iter().collect();
+            let start = post_dash[0].0.start;
+            let end = post_dash[post_dash.len() - 1].0.end;
+            let all = &s[start..end];
 
-                    while let Some(c) = current {
-                        if let Ok(subtype) = c.parse::<Subtype>() {
-                            type_line.add_subtype(subtype);
-                            current = parts.next();
-                        } else {
-                            break;
+            if let Some(subtype) = Subtype::known(all, &types) {
+                type_line.add_subtype(subtype);
+            } else if lenient && types == [Type::Plane] {
+                // Plane types can be several words long (205.3n).
+                type_line.add_subtype(Subtype::from_name(all, &types));
+            } else {
+                let is_any_dash = |word: &str| is_dash(word) || DASH_LOOKALIKES.contains(&word);
+                let mut i = 0;
+                while i < post_dash.len() {
+                    let (span, word) = &post_dash[i];
+                    if is_any_dash(word) {
+                        if lenient {
+                            i += 1;
+                            continue;
                         }
+                        return Err(TypeLineReadError {
+                            kind: TypeLineReadErrorKind::UnexpectedDash,
+                            word: word.to_string(),
+                            span: span.clone(),
+                            section: TypeLineSection::Subtypes,
+                            suggestions: vec![],
+                        });
                     }
 
-                    if current.is_some() {
-                        return Err(TypeLineReadError::ExtraParts(current.unwrap().to_string()));
-                    }
+                    // Subtypes such as `Time Lord` are several words long, so
+                    // the longest run of words naming a known subtype wins.
+                    let run_end = post_dash[i..]
+                        .iter()
+                        .position(|(_, word)| is_any_dash(word))
+                        .map_or(post_dash.len(), |n| i + n);
+                    let known = (i + 1..=run_end).rev().find_map(|end| {
+                        let name = &s[span.start..post_dash[end - 1].0.end];
+                        Subtype::known(name, &types).map(|subtype| (subtype, end))
+                    });
+
+                    let (subtype, end) = match known {
+                        Some(known) => known,
+                        None if lenient => (Subtype::from_name(word, &types), i + 1),
+                        None => return Err(type_line.error(word, span, TypeLineSection::Subtypes)),
+                    };
+                    type_line.add_subtype(subtype);
+                    i = end;
                 }
             }
         }
 
         Ok(type_line)
     }
+
+    /// The error for an unreadable word in `section`, with suggestions
+    /// based on what has been read so far.
+    fn error(
+        &self,
+        word: &str,
+        span: &Range<usize>,
+        section: TypeLineSection,
+    ) -> TypeLineReadError {
+        let error = |kind, section, suggestions| TypeLineReadError {
+            kind,
+            word: word.to_string(),
+            span: span.clone(),
+            section,
+            suggestions,
+        };
+
+        if DASH_LOOKALIKES.contains(&word) {
+            let em_dash = vec![EM_DASH.to_string()];
+            return error(TypeLineReadErrorKind::NotAnEmDash, section, em_dash);
+        }
+
+        let names: Vec<&str> = match section {
+            TypeLineSection::Supertypes => {
+                // Before any card type, a typo could be in either.
+                let supertypes = suggestions(word, Supertype::NAMES.iter().cloned());
+                let types = suggestions(word, Type::NAMES.iter().cloned());
+                let closer = |a: &[String], b: &[String]| match (a.first(), b.first()) {
+                    (Some(a), Some(b)) => edit_distance(word, a) < edit_distance(word, b),
+                    (a, _) => a.is_some(),
+                };
+
+                return if closer(&supertypes, &types) {
+                    error(TypeLineReadErrorKind::UnknownWord, section, supertypes)
+                } else {
+                    error(
+                        TypeLineReadErrorKind::UnknownWord,
+                        TypeLineSection::Types,
+                        types,
+                    )
+                };
+            }
+            TypeLineSection::Types => Type::NAMES.to_vec(),
+            TypeLineSection::Subtypes => {
+                let types: Vec<_> = if self.types.is_empty() {
+                    Type::ALL.to_vec()
+                } else {
+                    self.types_iter().collect()
+                };
+                types
+                    .into_iter()
+                    .flat_map(Subtype::names_for)
+                    .cloned()
+                    .collect()
+            }
+        };
+
+        let suggestions = suggestions(word, names);
+        error(TypeLineReadErrorKind::UnknownWord, section, suggestions)
+    }
+
+    /// Whether every subtype on this type line is one this crate knows.
+    pub fn is_known(&self) -> bool {
+        self.subtypes.iter().all(Subtype::is_known)
+    }
 }
 
-impl_deserialize_with_fromstr!(TypeLine);
-impl_serialize_with_tostring!(TypeLine);
+/// Parses a type line in `ParseMode::Strict`.
+impl FromStr for TypeLine {
+    type Err = TypeLineReadError;
+
+    fn from_str(s: &str) -> Result<TypeLine, TypeLineReadError> {
+        TypeLine::parse(s, ParseMode::Strict)
+    }
+}
+
+/// The serialized form of a type line. Every creature type can't be
+/// written in the text, so a type line that has them is a map.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TypeLineRepr {
+    Text(String),
+    AllCreatureTypes {
+        type_line: String,
+        all_creature_types: bool,
+    },
+}
+
+/// Type lines are deserialized in `ParseMode::Lenient`, so loading card data
+/// never fails on new subtypes.
+impl<'de> Deserialize<'de> for TypeLine {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TypeLine, D::Error> {
+        let (s, all_creature_types) = match TypeLineRepr::deserialize(deserializer)? {
+            TypeLineRepr::Text(s) => (s, false),
+            TypeLineRepr::AllCreatureTypes {
+                type_line,
+                all_creature_types,
+            } => (type_line, all_creature_types),
+        };
+
+        let mut type_line = TypeLine::parse(&s, ParseMode::Lenient).map_err(de::Error::custom)?;
+        type_line.all_creature_types = all_creature_types;
+        Ok(type_line)
+    }
+}
+
+impl Serialize for TypeLine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = if self.all_creature_types {
+            TypeLineRepr::AllCreatureTypes {
+                type_line: self.to_string(),
+                all_creature_types: true,
+            }
+        } else {
+            TypeLineRepr::Text(self.to_string())
+        };
+        repr.serialize(serializer)
+    }
+}
 
 #[macro_export]
 macro_rules! type_line {
     ($($supertype:ident)* ; $($type_:ident)* ; $($subtype:ident)*) => {
         {
-            let mut type_line = TypeLine::new();
+            let mut type_line = $crate::card::TypeLine::new();
 
             $(
                 type_line.add_supertype($crate::type_::Supertype::$supertype);
@@ -221,6 +722,7 @@ macro_rules! type_line {
                     PlanarType::*,
                     LandType::*,
                     PlaneswalkerType::*,
+                    BattleType::*,
                     IntoSubtype,
                 };
 
@@ -234,11 +736,11 @@ macro_rules! type_line {
     };
 
     ($($type_:ident)* ; $($subtype:ident)*) => {
-        type_line!(; $($type_ )* ; $($subtype )*)
+        $crate::type_line!(; $($type_ )* ; $($subtype )*)
     };
 
     ($($type_:ident)*) => {
-        type_line!(; $($type_ )* ;)
+        $crate::type_line!(; $($type_ )* ;)
     };
 }
 
@@ -307,4 +809,223 @@ mod tests {
             assert!(!line.is_valid());
         }
     }
+
+    #[test]
+    fn type_line_violations() {
+        use self::TypeLineViolation::*;
+        use crate::type_::{CreatureType, PlaneswalkerType};
+
+        assert_eq!(type_line!(Creature; Human).validate(), vec![]);
+        assert_eq!(
+            type_line!(Legendary; ; Human).validate(),
+            vec![
+                NoCardType,
+                InvalidSubtype(Subtype::Creature(CreatureType::Human)),
+            ]
+        );
+        assert_eq!(
+            type_line!(Basic; Artifact; Equipment).validate(),
+            vec![BasicNonLand]
+        );
+        assert_eq!(
+            type_line!(Ongoing; Enchantment;).validate(),
+            vec![OngoingNonScheme]
+        );
+        assert_eq!(
+            type_line!(Kindred Creature; Elf).validate(),
+            vec![KindredWithoutType(Type::Kindred)]
+        );
+        assert!(type_line!(Kindred Instant; Elf).validate().is_empty());
+        assert_eq!(
+            type_line!(Plane Creature; Dominaria Angel).validate(),
+            vec![NotAlone(Type::Plane)]
+        );
+        assert_eq!(
+            type_line!(Plane; Dominaria Angel).validate(),
+            vec![InvalidSubtype(Subtype::Creature(CreatureType::Angel))]
+        );
+
+        let violations = type_line!(Legendary; Planeswalker; Jace Chandra).validate();
+        assert_eq!(violations, vec![MultiplePlaneswalkerTypes(2)]);
+        assert_eq!(violations[0].rule(), "205.3j");
+        assert_eq!(
+            violations[0].to_string(),
+            "there are 2 planeswalker types instead of one (205.3j)"
+        );
+
+        let jace = Subtype::Planeswalker(PlaneswalkerType::Jace);
+        assert_eq!(
+            InvalidSubtype(jace).to_string(),
+            "`Jace` is not a subtype of any of the card types (205.3d)"
+        );
+    }
+
+    #[test]
+    fn type_changing_effects() {
+        use crate::type_::{CreatureType::*, LandType::*, Subtype::*};
+
+        // Changeling
+        let mut line = type_line!(Kindred Instant;);
+        line.set_all_creature_types();
+        assert!(line.has_subtype(Creature(Goblin)));
+        assert!(line.has_subtype(Creature(CreatureType::Other("Gnoll".to_string()))));
+        assert!(!line.has_subtype(Land(Forest)));
+        assert_eq!(line.to_string(), "Kindred Instant");
+
+        let json = serde_json::to_string(&line).unwrap();
+        assert_eq!(serde_json::from_str::<TypeLine>(&json).unwrap(), line);
+        let plain = type_line!(Kindred Instant;);
+        assert_eq!(
+            serde_json::to_string(&plain).unwrap(),
+            r#""Kindred Instant""#
+        );
+        assert_ne!(serde_json::from_str::<TypeLine>(&json).unwrap(), plain);
+
+        // "Loses all creature types"
+        let mut line = type_line!(Creature; Shapeshifter);
+        line.set_all_creature_types();
+        line.remove_subtypes_of(Type::Creature);
+        assert!(!line.has_subtype(Creature(Goblin)));
+        assert_eq!(line, type_line!(Creature;));
+
+        // "Becomes an artifact in addition to its other types"
+        let mut line = type_line!(Creature; Human Wizard);
+        line.add_type(Type::Artifact);
+        assert_eq!(line, type_line!(Creature Artifact; Human Wizard));
+
+        // "Becomes an artifact", replacing its card types
+        let mut line = type_line!(Creature; Human Wizard);
+        line.set_types(vec![Type::Artifact]);
+        assert_eq!(line, type_line!(Artifact));
+
+        let mut line = type_line!(Artifact; Equipment);
+        line.set_types(vec![Type::Artifact, Type::Creature]);
+        assert_eq!(line, type_line!(Artifact Creature; Equipment));
+
+        // Blood Moon
+        let mut line = type_line!(Land Creature; Forest Dryad);
+        line.set_subtypes(vec![Land(Mountain)]);
+        assert_eq!(line, type_line!(Land Creature; Dryad Mountain));
+
+        let mut line = type_line!(Land; Urzas Tower);
+        line.set_subtypes(vec![Land(Mountain)]);
+        assert_eq!(line, type_line!(Land; Mountain));
+
+        let mut line = type_line!(Creature; Shapeshifter);
+        line.set_all_creature_types();
+        line.set_subtypes(vec![Creature(Frog)]);
+        assert!(!line.has_subtype(Creature(Goblin)));
+        assert_eq!(line, type_line!(Creature; Frog));
+    }
+
+    #[test]
+    fn read_errors() {
+        use self::TypeLineReadErrorKind::*;
+
+        let error = "Legendary Creture \u{2014} Elf"
+            .parse::<TypeLine>()
+            .unwrap_err();
+        assert_eq!(error.kind(), UnknownWord);
+        assert_eq!(error.span(), 10..17);
+        assert_eq!(error.section(), TypeLineSection::Types);
+        assert_eq!(error.suggestions(), ["Creature"]);
+        assert_eq!(
+            error.to_string(),
+            "unknown card type `Creture` at bytes 10..17; did you mean `Creature`?"
+        );
+
+        let error = "Legendery Creature".parse::<TypeLine>().unwrap_err();
+        assert_eq!(error.section(), TypeLineSection::Supertypes);
+        assert_eq!(error.suggestions(), ["Legendary"]);
+
+        let error = "Creature \u{2014} Elv Druid"
+            .parse::<TypeLine>()
+            .unwrap_err();
+        assert_eq!(error.span(), 13..16);
+        assert_eq!(error.section(), TypeLineSection::Subtypes);
+        assert_eq!(error.suggestions(), ["Elf", "Elk"]);
+
+        let error = "Creature - Elf Druid".parse::<TypeLine>().unwrap_err();
+        assert_eq!(error.kind(), NotAnEmDash);
+        assert_eq!(error.span(), 9..10);
+        assert_eq!(error.suggestions(), ["\u{2014}"]);
+
+        let error = "Creature \u{2014} Elf \u{2014} Druid"
+            .parse::<TypeLine>()
+            .unwrap_err();
+        assert_eq!(error.kind(), UnexpectedDash);
+    }
+
+    #[test]
+    fn ascii_dashes() {
+        let options = ParseOptions {
+            ascii_dashes: true,
+            ..Default::default()
+        };
+
+        for line in &["Creature - Elf Druid", "Creature -- Elf Druid"] {
+            assert_eq!(
+                TypeLine::parse_with(line, options).unwrap(),
+                type_line!(Creature; Elf Druid)
+            );
+        }
+
+        assert_eq!(
+            "Creature\u{2014}Elf".parse::<TypeLine>().unwrap(),
+            type_line!(Creature; Elf)
+        );
+        assert_eq!(
+            "Land \u{2014} Power-Plant".parse::<TypeLine>().unwrap(),
+            type_line!(Land; PowerPlant)
+        );
+    }
+
+    #[test]
+    fn multi_word_subtypes() {
+        use crate::type_::CreatureType;
+
+        let line = "Legendary Creature \u{2014} Time Lord Doctor";
+        let expected = type_line!(Legendary; Creature; TimeLord Doctor);
+        assert_eq!(line.parse::<TypeLine>().unwrap(), expected);
+        assert_eq!(TypeLine::parse(line, ParseMode::Lenient).unwrap(), expected);
+        assert_eq!(expected.to_string(), line);
+
+        let lenient =
+            TypeLine::parse("Creature \u{2014} Time Lord Wizzard", ParseMode::Lenient).unwrap();
+        assert!(lenient.has_subtype(Subtype::Creature(CreatureType::TimeLord)));
+        assert!(lenient.has_subtype(Subtype::Creature(CreatureType::Other(
+            "Wizzard".to_string()
+        ))));
+    }
+
+    #[test]
+    fn unknown_subtypes() {
+        use crate::type_::{CreatureType, LandType, PlanarType};
+
+        let line = "Legendary Creature \u{2014} Human Wizzard";
+        let error = line.parse::<TypeLine>().unwrap_err();
+        assert_eq!(error.word(), "Wizzard");
+        assert_eq!(error.section(), TypeLineSection::Subtypes);
+
+        let lenient = TypeLine::parse(line, ParseMode::Lenient).unwrap();
+        assert!(lenient.has_subtype(Subtype::Creature(CreatureType::Human)));
+        assert!(lenient.has_subtype(Subtype::Creature(CreatureType::Other(
+            "Wizzard".to_string()
+        ))));
+        assert!(!lenient.is_known());
+        assert_eq!(lenient.to_string(), line);
+
+        let lenient = TypeLine::parse("Land \u{2014} Grove", ParseMode::Lenient).unwrap();
+        assert!(lenient.has_subtype(Subtype::Land(LandType::Other("Grove".to_string()))));
+        assert!(lenient.is_valid());
+
+        let lenient = TypeLine::parse("Plane \u{2014} The Wilds", ParseMode::Lenient).unwrap();
+        assert!(lenient.has_subtype(Subtype::Plane(PlanarType::Other("The Wilds".to_string()))));
+
+        let lenient = TypeLine::parse("Frobnicate Artifact", ParseMode::Lenient).unwrap();
+        assert_eq!(lenient, type_line!(Artifact));
+
+        let line: TypeLine = serde_json::from_str("\"Creature \u{2014} Wizzard\"").unwrap();
+        assert!(!line.is_known());
+    }
 }
@@ -0,0 +1,304 @@
+use crate::macros::{impl_deserialize_with_fromstr, impl_serialize_with_tostring};
+use std::{fmt, str::FromStr};
+
+const INFINITY: &str = "\u{221e}";
+
+/// Supplies the values that printed power, toughness and loyalty can
+/// depend on.
+pub trait ValueContext {
+    /// The value of `*`, as defined by a characteristic-defining ability
+    /// (604.3). These abilities work in every zone.
+    fn star(&self) -> i32;
+
+    /// The value of X (107.3).
+    fn x(&self) -> i32;
+}
+
+/// A `ValueContext` whose values are already known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KnownValues {
+    pub star: i32,
+    pub x: i32,
+}
+
+impl ValueContext for KnownValues {
+    fn star(&self) -> i32 {
+        self.star
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+}
+
+/// A printed power or toughness (208).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PtValue {
+    Number(i32),
+    /// `*`, `1+*` or `7-*`: a number plus or minus a value defined by a
+    /// characteristic-defining ability (208.2).
+    Star {
+        base: i32,
+        negated: bool,
+    },
+    X,
+    /// `?`.
+    Unknown,
+    /// `∞`.
+    Infinity,
+    /// Anything else, such as `*²` or `½` on silver-bordered cards, kept as
+    /// printed.
+    Other(String),
+}
+
+impl PtValue {
+    /// Whether this value is defined by a characteristic-defining ability
+    /// or a choice rather than printed as a number.
+    pub fn is_variable(&self) -> bool {
+        matches!(self, PtValue::Star { .. } | PtValue::X)
+    }
+
+    /// The value of this in `context`, or `None` if it isn't a number.
+    pub fn evaluate(&self, context: &impl ValueContext) -> Option<i32> {
+        match *self {
+            PtValue::Number(n) => Some(n),
+            PtValue::Star {
+                base,
+                negated: false,
+            } => Some(base + context.star()),
+            PtValue::Star {
+                base,
+                negated: true,
+            } => Some(base - context.star()),
+            PtValue::X => Some(context.x()),
+            PtValue::Unknown | PtValue::Infinity | PtValue::Other(_) => None,
+        }
+    }
+}
+
+impl From<i32> for PtValue {
+    fn from(n: i32) -> PtValue {
+        PtValue::Number(n)
+    }
+}
+
+impl fmt::Display for PtValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PtValue::Number(n) => write!(f, "{}", n),
+            PtValue::Star {
+                base: 0,
+                negated: false,
+            } => write!(f, "*"),
+            PtValue::Star {
+                base: 0,
+                negated: true,
+            } => write!(f, "-*"),
+            PtValue::Star { base, negated } => {
+                write!(f, "{}{}*", base, if *negated { '-' } else { '+' })
+            }
+            PtValue::X => write!(f, "X"),
+            PtValue::Unknown => write!(f, "?"),
+            PtValue::Infinity => write!(f, "{}", INFINITY),
+            PtValue::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseValueError {
+    value: String,
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid printed value `{}`", self.value)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+/// Parses a plain integer. `+1` isn't accepted so that it keeps its sign
+/// when displayed.
+fn parse_number(s: &str) -> Option<i32> {
+    if s.starts_with('+') {
+        return None;
+    }
+    s.parse().ok()
+}
+
+impl FromStr for PtValue {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<PtValue, ParseValueError> {
+        if s.is_empty() || s.contains(char::is_whitespace) {
+            return Err(ParseValueError {
+                value: s.to_string(),
+            });
+        }
+
+        if let Some(n) = parse_number(s) {
+            return Ok(PtValue::Number(n));
+        }
+
+        let star = |base: &str, negated| {
+            parse_number(base)
+                .filter(|&base| base != 0)
+                .map(|base| PtValue::Star { base, negated })
+        };
+
+        let value = match s {
+            "*" => Some(PtValue::Star {
+                base: 0,
+                negated: false,
+            }),
+            "-*" => Some(PtValue::Star {
+                base: 0,
+                negated: true,
+            }),
+            "X" => Some(PtValue::X),
+            "?" => Some(PtValue::Unknown),
+            INFINITY => Some(PtValue::Infinity),
+            _ => {
+                if let Some(base) = s.strip_suffix("+*") {
+                    star(base, false)
+                } else if let Some(base) = s.strip_suffix("-*") {
+                    star(base, true)
+                } else {
+                    None
+                }
+            }
+        };
+
+        Ok(value.unwrap_or_else(|| PtValue::Other(s.to_string())))
+    }
+}
+
+impl_deserialize_with_fromstr!(PtValue);
+impl_serialize_with_tostring!(PtValue);
+
+/// A printed loyalty (209).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LoyaltyValue {
+    Number(i32),
+    X,
+    /// Anything else, such as `*` or `1d4+1`, kept as printed.
+    Other(String),
+}
+
+impl LoyaltyValue {
+    /// The value of this in `context`, or `None` if it isn't a number.
+    pub fn evaluate(&self, context: &impl ValueContext) -> Option<i32> {
+        match *self {
+            LoyaltyValue::Number(n) => Some(n),
+            LoyaltyValue::X => Some(context.x()),
+            LoyaltyValue::Other(_) => None,
+        }
+    }
+}
+
+impl From<i32> for LoyaltyValue {
+    fn from(n: i32) -> LoyaltyValue {
+        LoyaltyValue::Number(n)
+    }
+}
+
+impl fmt::Display for LoyaltyValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoyaltyValue::Number(n) => write!(f, "{}", n),
+            LoyaltyValue::X => write!(f, "X"),
+            LoyaltyValue::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for LoyaltyValue {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<LoyaltyValue, ParseValueError> {
+        if s.is_empty() || s.contains(char::is_whitespace) {
+            return Err(ParseValueError {
+                value: s.to_string(),
+            });
+        }
+
+        Ok(match parse_number(s) {
+            Some(n) => LoyaltyValue::Number(n),
+            None if s == "X" => LoyaltyValue::X,
+            None => LoyaltyValue::Other(s.to_string()),
+        })
+    }
+}
+
+impl_deserialize_with_fromstr!(LoyaltyValue);
+impl_serialize_with_tostring!(LoyaltyValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pt_round_trip() {
+        #[rustfmt::skip]
+        let pairs = vec![
+            ("3", PtValue::Number(3)),
+            ("-1", PtValue::Number(-1)),
+            ("*", PtValue::Star { base: 0, negated: false }),
+            ("1+*", PtValue::Star { base: 1, negated: false }),
+            ("7-*", PtValue::Star { base: 7, negated: true }),
+            ("X", PtValue::X),
+            ("?", PtValue::Unknown),
+            ("\u{221e}", PtValue::Infinity),
+            ("*\u{b2}", PtValue::Other("*\u{b2}".to_string())),
+            ("+1", PtValue::Other("+1".to_string())),
+        ];
+
+        for (string, value) in pairs {
+            assert_eq!(string.parse::<PtValue>().unwrap(), value);
+            assert_eq!(value.to_string(), string);
+            assert_eq!(
+                serde_json::to_string(&value).unwrap(),
+                format!("\"{}\"", string)
+            );
+        }
+
+        assert!("".parse::<PtValue>().is_err());
+        assert!("1 + *".parse::<PtValue>().is_err());
+    }
+
+    #[test]
+    fn evaluate_in_context() {
+        let context = KnownValues { star: 4, x: 2 };
+
+        let values: Vec<PtValue> = vec!["3", "*", "1+*", "7-*", "X", "?"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let evaluated: Vec<_> = values.iter().map(|v| v.evaluate(&context)).collect();
+
+        assert_eq!(
+            evaluated,
+            vec![Some(3), Some(4), Some(5), Some(3), Some(2), None]
+        );
+        assert!(values[1].is_variable());
+        assert!(!values[0].is_variable());
+    }
+
+    #[test]
+    fn loyalty() {
+        let context = KnownValues { star: 0, x: 3 };
+
+        for (string, value) in [
+            ("4", LoyaltyValue::Number(4)),
+            ("X", LoyaltyValue::X),
+            ("1d4+1", LoyaltyValue::Other("1d4+1".to_string())),
+        ] {
+            assert_eq!(string.parse::<LoyaltyValue>().unwrap(), value);
+            assert_eq!(value.to_string(), string);
+        }
+
+        assert_eq!(LoyaltyValue::X.evaluate(&context), Some(3));
+        assert_eq!(LoyaltyValue::from(5).evaluate(&context), Some(5));
+    }
+}
@@ -0,0 +1,71 @@
+//! Edit distance between names, for "did you mean" suggestions.
+
+/// The Levenshtein distance between `a` and `b`, ignoring case.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The candidates close enough to `word` to be worth suggesting, closest
+/// first.
+pub(crate) fn suggestions<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let max_distance = (word.chars().count() / 3).max(1);
+    let mut close: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+
+    close.sort();
+    close.dedup();
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Creture", "Creature"), 1);
+        assert_eq!(edit_distance("creature", "Creature"), 0);
+        assert_eq!(edit_distance("Jötun", "Jotun"), 1);
+    }
+
+    #[test]
+    fn closest_suggestions_first() {
+        let candidates = vec!["Elf", "Elk", "Elemental", "Eldrazi"];
+
+        assert_eq!(suggestions("Elv", candidates.clone()), vec!["Elf", "Elk"]);
+        assert_eq!(
+            suggestions("Elementl", candidates.clone()),
+            vec!["Elemental"]
+        );
+        assert!(suggestions("Goblin", candidates).is_empty());
+    }
+}
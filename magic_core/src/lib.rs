@@ -1,13 +1,18 @@
 pub mod ability;
 pub mod card;
+mod edit_distance;
 pub mod mana;
 pub mod phase;
+pub mod symbol;
 pub mod traits;
 pub mod type_;
+pub mod zone;
 
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::iter::FromIterator;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+use std::str::FromStr;
 
 pub mod macros {
     pub use magic_macros::*;
@@ -37,15 +42,18 @@ impl Color {
     }
 
     pub fn color_pie_order(color1: Color, color2: Color) -> (Color, Color) {
-        let c = (color1 as u8, color2 as u8);
-        if c.0 > c.1 {
-            Color::color_pie_order(color2, color1)
-        } else if c.1 - c.0 > 2 {
-            (color2, color1)
-        } else {
-            (color1, color2)
+        let colors = ColorIdentity::from_iter(vec![color1, color2]).ordered();
+        match colors[..] {
+            [first, second] => (first, second),
+            _ => (color1, color2),
         }
     }
+
+    /// The color `steps` places clockwise from this one on the color pie.
+    fn clockwise(self, steps: u8) -> Color {
+        let index = (self as u8 + steps) % 5;
+        Color::iterator().nth(index as usize).unwrap()
+    }
 }
 
 impl fmt::Display for Color {
@@ -63,19 +71,151 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseColorError {
     _priv: (),
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "provided string was not a recognized color")
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        use self::Color::*;
+
+        match s.to_ascii_lowercase().as_str() {
+            "w" | "white" => Ok(White),
+            "u" | "blue" => Ok(Blue),
+            "b" | "black" => Ok(Black),
+            "r" | "red" => Ok(Red),
+            "g" | "green" => Ok(Green),
+            _ => Err(ParseColorError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct ColorIdentity(u8);
 
+/// Names of color combinations, keyed by `ColorIdentity` bits.
+#[rustfmt::skip]
+const NAMES: &[(u8, &str)] = &[
+    (0b00000, "Colorless"),
+    (0b00001, "White"),
+    (0b00010, "Blue"),
+    (0b00100, "Black"),
+    (0b01000, "Red"),
+    (0b10000, "Green"),
+    // Guilds
+    (0b00011, "Azorius"),
+    (0b00110, "Dimir"),
+    (0b01100, "Rakdos"),
+    (0b11000, "Gruul"),
+    (0b10001, "Selesnya"),
+    (0b00101, "Orzhov"),
+    (0b01010, "Izzet"),
+    (0b10100, "Golgari"),
+    (0b01001, "Boros"),
+    (0b10010, "Simic"),
+    // Shards
+    (0b10011, "Bant"),
+    (0b00111, "Esper"),
+    (0b01110, "Grixis"),
+    (0b11100, "Jund"),
+    (0b11001, "Naya"),
+    // Wedges
+    (0b10101, "Abzan"),
+    (0b01011, "Jeskai"),
+    (0b10110, "Sultai"),
+    (0b01101, "Mardu"),
+    (0b11010, "Temur"),
+    // Nephilim
+    (0b11110, "Glint-Eye"),
+    (0b11101, "Dune-Brood"),
+    (0b11011, "Ink-Treader"),
+    (0b10111, "Witch-Maw"),
+    (0b01111, "Yore-Tiller"),
+    (0b11111, "Five-Color"),
+];
+
+/// Other accepted names, such as the older wedge names.
+#[rustfmt::skip]
+const ALIASES: &[(u8, &str)] = &[
+    (0b10101, "Junk"),
+    (0b01011, "American"),
+    (0b11110, "Chaos"),
+    (0b11101, "Aggression"),
+    (0b11011, "Altruism"),
+    (0b10111, "Growth"),
+    (0b01111, "Artifice"),
+    (0b11111, "Rainbow"),
+];
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 impl ColorIdentity {
     pub fn new() -> ColorIdentity {
         Default::default()
     }
 
+    /// Every one of the 32 color identities, from colorless to all five
+    /// colors.
+    pub fn all() -> impl Iterator<Item = ColorIdentity> {
+        (0..32).map(ColorIdentity)
+    }
+
+    /// The name of this combination of colors, such as "Boros" or
+    /// "Esper".
+    pub fn name(self) -> &'static str {
+        NAMES
+            .iter()
+            .find(|&&(bits, _)| bits == self.0)
+            .map(|&(_, name)| name)
+            .unwrap()
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.is_colorless()
+    }
+
+    pub fn union(self, other: ColorIdentity) -> ColorIdentity {
+        ColorIdentity(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: ColorIdentity) -> ColorIdentity {
+        ColorIdentity(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: ColorIdentity) -> ColorIdentity {
+        ColorIdentity(self.0 & !other.0)
+    }
+
+    /// Whether every color in this identity is also in `other`, as when
+    /// checking a card against a commander's color identity (903.4).
+    pub fn is_subset(self, other: ColorIdentity) -> bool {
+        self.difference(other).is_colorless()
+    }
+
+    pub fn is_superset(self, other: ColorIdentity) -> bool {
+        other.is_subset(self)
+    }
+
     pub fn is_colorless(self) -> bool {
         self.0.trailing_zeros() >= 5
     }
@@ -92,8 +232,34 @@ impl ColorIdentity {
         self.0 &= !(1 << color as u8);
     }
 
+    /// The colors in this identity, in the order they are printed on
+    /// cards. See `ordered`.
     pub fn colors(self) -> impl Iterator<Item = Color> {
-        Color::iterator().filter(move |c| self.has(*c))
+        self.ordered().into_iter()
+    }
+
+    /// The colors in this identity in canonical order. Going clockwise
+    /// around the color pie, the colors are visited in equal steps:
+    /// adjacent colors (`GW`, `BRG`, `WUBR`) one step at a time, and
+    /// enemy pairs and wedges (`RW`, `WBG`, `GUR`) two steps at a time.
+    pub fn ordered(self) -> Vec<Color> {
+        let len = self.len() as u8;
+
+        for &step in [1, 2].iter() {
+            for start in Color::iterator().filter(|&c| self.has(c)) {
+                let ordered: Vec<Color> = (0..len).map(|i| start.clockwise(i * step)).collect();
+                if ordered.iter().all(|&c| self.has(c)) {
+                    return ordered;
+                }
+            }
+        }
+
+        Color::iterator().filter(|&c| self.has(c)).collect()
+    }
+
+    /// The position of `color` in `ordered`, if it is in this identity.
+    pub fn position(self, color: Color) -> Option<usize> {
+        self.ordered().iter().position(|&c| c == color)
     }
 }
 
@@ -107,6 +273,120 @@ impl FromIterator<Color> for ColorIdentity {
     }
 }
 
+impl From<Color> for ColorIdentity {
+    fn from(color: Color) -> ColorIdentity {
+        ColorIdentity(1 << color as u8)
+    }
+}
+
+macro_rules! impl_set_op {
+    ($Op:ident, $meth:ident, $OpAssign:ident, $meth_assign:ident, $set_meth:ident) => {
+        impl $Op for ColorIdentity {
+            type Output = ColorIdentity;
+
+            fn $meth(self, other: ColorIdentity) -> ColorIdentity {
+                self.$set_meth(other)
+            }
+        }
+
+        impl $OpAssign for ColorIdentity {
+            fn $meth_assign(&mut self, other: ColorIdentity) {
+                *self = self.$set_meth(other);
+            }
+        }
+    };
+}
+
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+impl_set_op!(BitAnd, bitand, BitAndAssign, bitand_assign, intersection);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, difference);
+
+impl FromStr for ColorIdentity {
+    type Err = ParseColorError;
+
+    /// Parses color letters in any order (`"WUBRG"`, `"gw"`), `"C"` for
+    /// colorless, or the name of a color combination (`"Esper"`).
+    fn from_str(s: &str) -> Result<ColorIdentity, ParseColorError> {
+        let name = normalize_name(s);
+        let named = NAMES
+            .iter()
+            .chain(ALIASES.iter())
+            .find(|&&(_, n)| normalize_name(n) == name);
+        if let Some(&(bits, _)) = named {
+            return Ok(ColorIdentity(bits));
+        }
+
+        if name == "c" {
+            return Ok(ColorIdentity::new());
+        }
+
+        if name.is_empty() {
+            return Err(ParseColorError { _priv: () });
+        }
+
+        name.chars()
+            .map(|c| c.to_string().parse::<Color>())
+            .collect()
+    }
+}
+
+impl Serialize for ColorIdentity {
+    /// Serializes as a list of color letters, as MTGJSON and Scryfall do.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for color in self.colors() {
+            seq.serialize_element(&color)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorIdentity {
+    /// Deserializes from a list of colors or from any string accepted by
+    /// `FromStr`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = ColorIdentity;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a list of colors or a color identity string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<ColorIdentity, E>
+            where
+                E: de::Error,
+            {
+                value
+                    .parse()
+                    .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<ColorIdentity, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut res = ColorIdentity::new();
+                while let Some(color) = seq.next_element::<Color>()? {
+                    res.add(color);
+                }
+                Ok(res)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
 impl fmt::Display for ColorIdentity {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.is_colorless() {
@@ -139,11 +419,113 @@ mod tests {
     #[test]
     fn test_color_identity() {
         assert_eq!(ColorIdentity(0b00000).to_string(), "C");
-        assert_eq!(ColorIdentity(0b10001).to_string(), "WG");
+        assert_eq!(ColorIdentity(0b10001).to_string(), "GW");
         assert_eq!(ColorIdentity(0b01110).to_string(), "UBR");
         assert_eq!(ColorIdentity(0b01000).to_string(), "R");
     }
 
+    #[test]
+    fn color_identity_parse() {
+        #[rustfmt::skip]
+        let pairs = [
+            ("C", 0b00000),
+            ("Colorless", 0b00000),
+            ("W", 0b00001),
+            ("WUBRG", 0b11111),
+            ("gw", 0b10001),
+            ("Esper", 0b00111),
+            ("boros", 0b01001),
+            ("Glint-Eye", 0b11110),
+            ("glint eye", 0b11110),
+            ("Junk", 0b10101),
+            ("Five-Color", 0b11111),
+        ];
+
+        for (string, bits) in pairs.iter() {
+            assert_eq!(
+                string.parse::<ColorIdentity>().unwrap(),
+                ColorIdentity(*bits)
+            );
+        }
+
+        assert!("".parse::<ColorIdentity>().is_err());
+        assert!("WX".parse::<ColorIdentity>().is_err());
+        assert!("Ravnica".parse::<ColorIdentity>().is_err());
+    }
+
+    #[test]
+    fn color_identity_names() {
+        assert_eq!(ColorIdentity::all().count(), 32);
+        for identity in ColorIdentity::all() {
+            assert_eq!(identity.name().parse::<ColorIdentity>().unwrap(), identity);
+            assert_eq!(
+                identity.to_string().parse::<ColorIdentity>().unwrap(),
+                identity
+            );
+        }
+
+        assert_eq!(ColorIdentity(0b01001).name(), "Boros");
+    }
+
+    #[test]
+    fn color_identity_set_ops() {
+        let esper: ColorIdentity = "Esper".parse().unwrap();
+        let azorius: ColorIdentity = "Azorius".parse().unwrap();
+        let red = ColorIdentity::from(Color::Red);
+
+        assert_eq!(azorius | ColorIdentity::from(Color::Black), esper);
+        assert_eq!(
+            esper & "Rakdos".parse().unwrap(),
+            ColorIdentity::from(Color::Black)
+        );
+        assert_eq!(esper - azorius, ColorIdentity::from(Color::Black));
+        assert!(azorius.is_subset(esper));
+        assert!(esper.is_superset(azorius));
+        assert!(!red.is_subset(esper));
+        assert!(ColorIdentity::new().is_subset(red));
+        assert_eq!(esper.len(), 3);
+    }
+
+    #[test]
+    fn color_identity_serde() {
+        let identity: ColorIdentity = serde_json::from_str(r#"["U", "W"]"#).unwrap();
+        assert_eq!(identity, "Azorius".parse().unwrap());
+        assert_eq!(serde_json::to_string(&identity).unwrap(), r#"["W","U"]"#);
+
+        let identity: ColorIdentity = serde_json::from_str(r#""Temur""#).unwrap();
+        assert_eq!(identity, "GUR".parse().unwrap());
+
+        assert!(serde_json::from_str::<ColorIdentity>(r#"["X"]"#).is_err());
+    }
+
+    #[test]
+    fn color_identity_order() {
+        #[rustfmt::skip]
+        let names = [
+            ("C", "C"), ("W", "W"), ("U", "U"), ("B", "B"), ("R", "R"), ("G", "G"),
+            ("Azorius", "WU"), ("Dimir", "UB"), ("Rakdos", "BR"), ("Gruul", "RG"),
+            ("Selesnya", "GW"), ("Orzhov", "WB"), ("Izzet", "UR"), ("Golgari", "BG"),
+            ("Boros", "RW"), ("Simic", "GU"),
+            ("Bant", "GWU"), ("Esper", "WUB"), ("Grixis", "UBR"), ("Jund", "BRG"),
+            ("Naya", "RGW"),
+            ("Abzan", "WBG"), ("Jeskai", "URW"), ("Sultai", "BGU"), ("Mardu", "RWB"),
+            ("Temur", "GUR"),
+            ("Glint-Eye", "UBRG"), ("Dune-Brood", "BRGW"), ("Ink-Treader", "RGWU"),
+            ("Witch-Maw", "GWUB"), ("Yore-Tiller", "WUBR"),
+            ("Five-Color", "WUBRG"),
+        ];
+
+        for (name, order) in names.iter() {
+            let identity: ColorIdentity = name.parse().unwrap();
+            assert_eq!(identity.to_string(), *order, "{}", name);
+        }
+
+        let temur: ColorIdentity = "Temur".parse().unwrap();
+        assert_eq!(temur.position(Color::Green), Some(0));
+        assert_eq!(temur.position(Color::Red), Some(2));
+        assert_eq!(temur.position(Color::White), None);
+    }
+
     #[test]
     fn color_pie_order() {
         use self::Color::*;
@@ -167,7 +549,7 @@ mod tests {
                     continue;
                 }
                 let pair = Color::color_pie_order(c1, c2);
-                assert!(valid_pairs.iter().any(|&vp| vp == pair));
+                assert!(valid_pairs.contains(&pair));
             }
         }
     }
@@ -0,0 +1,916 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+use super::{Color, ColorIdentity};
+use crate::macros::{impl_deserialize_with_fromstr, impl_serialize_with_tostring};
+
+mod modifier;
+mod pool;
+
+pub use self::modifier::CostModifier;
+pub use self::pool::{Mana, ManaExpiry, ManaPool, ManaRestriction, Payment, SpendTarget};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ManaSymbol {
+    Generic(u32),
+    Colored(Color),
+    Colorless,
+    Variable,
+    Hybrid(Color, Color),
+    MonoHybrid(Color),
+    Phyrexian(Color),
+    /// Two-color hybrid Phyrexian mana, such as `{G/U/P}`.
+    HybridPhyrexian(Color, Color),
+    /// `{½}`, or `{HW}` and friends for half of one colored mana.
+    Half(Option<Color>),
+    Snow,
+}
+
+impl fmt::Display for ManaSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ManaSymbol::*;
+
+        write!(f, "{{")?;
+        match *self {
+            Generic(n) => write!(f, "{}", n),
+            Colored(c) => write!(f, "{}", c),
+            Colorless => write!(f, "C"),
+            Variable => write!(f, "X"),
+            Hybrid(c1, c2) => {
+                let (c1, c2) = Color::color_pie_order(c1, c2);
+                write!(f, "{}/{}", c1, c2)
+            }
+            MonoHybrid(c) => write!(f, "2/{}", c),
+            Phyrexian(c) => write!(f, "{}/P", c),
+            HybridPhyrexian(c1, c2) => {
+                let (c1, c2) = Color::color_pie_order(c1, c2);
+                write!(f, "{}/{}/P", c1, c2)
+            }
+            Half(None) => write!(f, "\u{bd}"),
+            Half(Some(c)) => write!(f, "H{}", c),
+            Snow => write!(f, "S"),
+        }?;
+        write!(f, "}}")
+    }
+}
+
+impl ManaSymbol {
+    /// The colors of this symbol (202.2). Hybrid symbols are all of
+    /// their colors, and Phyrexian symbols are their color.
+    pub fn colors(self) -> ColorIdentity {
+        use self::ManaSymbol::*;
+
+        let mut colors = ColorIdentity::new();
+        match self {
+            Colored(c) | MonoHybrid(c) | Phyrexian(c) | Half(Some(c)) => colors.add(c),
+            Hybrid(c1, c2) | HybridPhyrexian(c1, c2) => {
+                colors.add(c1);
+                colors.add(c2);
+            }
+            Generic(_) | Colorless | Variable | Half(None) | Snow => (),
+        }
+        colors
+    }
+
+    fn from_inner(inner: &str) -> Option<ManaSymbol> {
+        use self::ManaSymbol::*;
+
+        if !inner.is_empty() && inner.bytes().all(|b| b.is_ascii_digit()) {
+            return inner.parse().ok().map(Generic);
+        }
+
+        match inner {
+            "C" => return Some(Colorless),
+            "X" => return Some(Variable),
+            "S" => return Some(Snow),
+            "\u{bd}" => return Some(Half(None)),
+            _ => (),
+        }
+
+        if let Some(color) = inner.strip_prefix('H') {
+            return parse_color(color).map(|c| Half(Some(c)));
+        }
+
+        let mut parts = inner.split('/');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(c1), Some(c2), Some("P"), None) => match (parse_color(c1), parse_color(c2)) {
+                (Some(c1), Some(c2)) if c1 != c2 => Some(HybridPhyrexian(c1, c2)),
+                _ => None,
+            },
+            (_, _, Some(_), _) => None,
+            (Some(c), None, _, _) => parse_color(c).map(Colored),
+            (Some("2"), Some(c), _, _) | (Some(c), Some("2"), _, _) => {
+                parse_color(c).map(MonoHybrid)
+            }
+            (Some(c), Some("P"), _, _) => parse_color(c).map(Phyrexian),
+            (Some(c1), Some(c2), _, _) => match (parse_color(c1), parse_color(c2)) {
+                (Some(c1), Some(c2)) if c1 != c2 => Some(Hybrid(c1, c2)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse_color(s: &str) -> Option<Color> {
+    use self::Color::*;
+
+    match s {
+        "W" => Some(White),
+        "U" => Some(Blue),
+        "B" => Some(Black),
+        "R" => Some(Red),
+        "G" => Some(Green),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseManaCostErrorKind {
+    /// A `{` without a matching `}`.
+    Unclosed,
+    /// Text outside of braces.
+    UnexpectedText,
+    /// A well-formed `{...}` that is not a mana symbol.
+    UnknownSymbol,
+}
+
+/// Error returned when parsing a `ManaSymbol` or `ManaCost` fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseManaCostError {
+    kind: ParseManaCostErrorKind,
+    offset: usize,
+    symbol: String,
+}
+
+impl ParseManaCostError {
+    pub fn kind(&self) -> &ParseManaCostErrorKind {
+        &self.kind
+    }
+
+    /// The byte offset of the rejected symbol in the input.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The text of the rejected symbol, including braces if present.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl fmt::Display for ParseManaCostError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ParseManaCostErrorKind::Unclosed => "unclosed mana symbol",
+            ParseManaCostErrorKind::UnexpectedText => "unexpected text",
+            ParseManaCostErrorKind::UnknownSymbol => "unknown mana symbol",
+        };
+        write!(f, "{} `{}` at byte {}", what, self.symbol, self.offset)
+    }
+}
+
+impl std::error::Error for ParseManaCostError {}
+
+/// Splits `s` into `{...}` symbols, yielding each with its byte offset.
+fn symbols(s: &str) -> impl Iterator<Item = Result<(usize, ManaSymbol), ParseManaCostError>> + '_ {
+    let mut offset = 0;
+
+    std::iter::from_fn(move || {
+        let rest = &s[offset..];
+        if rest.is_empty() {
+            return None;
+        }
+
+        let start = offset;
+        let error = |kind, symbol: &str| ParseManaCostError {
+            kind,
+            offset: start,
+            symbol: symbol.to_string(),
+        };
+
+        if !rest.starts_with('{') {
+            let len = rest.find('{').unwrap_or(rest.len());
+            offset = s.len();
+            return Some(Err(error(
+                ParseManaCostErrorKind::UnexpectedText,
+                &rest[..len],
+            )));
+        }
+
+        let result = match rest.find('}') {
+            Some(end) => {
+                let symbol = &rest[..=end];
+                offset += end + 1;
+                ManaSymbol::from_inner(&symbol[1..end])
+                    .map(|sym| (start, sym))
+                    .ok_or_else(|| error(ParseManaCostErrorKind::UnknownSymbol, symbol))
+            }
+            None => {
+                offset = s.len();
+                Err(error(ParseManaCostErrorKind::Unclosed, rest))
+            }
+        };
+
+        Some(result)
+    })
+}
+
+impl FromStr for ManaSymbol {
+    type Err = ParseManaCostError;
+
+    fn from_str(s: &str) -> Result<ManaSymbol, ParseManaCostError> {
+        let mut iter = symbols(s);
+        let (_, symbol) = match iter.next() {
+            Some(result) => result?,
+            None => {
+                return Err(ParseManaCostError {
+                    kind: ParseManaCostErrorKind::UnknownSymbol,
+                    offset: 0,
+                    symbol: String::new(),
+                });
+            }
+        };
+
+        match iter.next() {
+            None => Ok(symbol),
+            Some(Ok((offset, _))) => Err(ParseManaCostError {
+                kind: ParseManaCostErrorKind::UnexpectedText,
+                offset,
+                symbol: s[offset..].to_string(),
+            }),
+            Some(Err(err)) => Err(err),
+        }
+    }
+}
+
+impl_deserialize_with_fromstr!(ManaSymbol);
+impl_serialize_with_tostring!(ManaSymbol);
+
+pub trait ConvertedManaCost {
+    fn converted_mana_cost(&self) -> usize;
+}
+
+impl ConvertedManaCost for ManaSymbol {
+    /// Half-mana symbols are worth ½, which rounds down to 0 here. A
+    /// `ManaCost` adds them up before rounding.
+    fn converted_mana_cost(&self) -> usize {
+        use self::ManaSymbol::*;
+
+        match *self {
+            Generic(n) => n as usize,
+            Colored(_) => 1,
+            Colorless => 1,
+            Variable => 0,
+            Hybrid(_, _) => 1,
+            MonoHybrid(_) => 2,
+            Phyrexian(_) => 1,
+            HybridPhyrexian(_, _) => 1,
+            Half(_) => 0,
+            Snow => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ManaCost {
+    symbols: Vec<ManaSymbol>,
+}
+
+#[derive(Default, PartialEq, Eq)]
+struct ManaCostComparator {
+    generic: usize,
+    variable: usize,
+    snow: usize,
+    colors: HashMap<Option<Color>, usize>,
+    hybrids: HashMap<(Color, Option<Color>), usize>,
+    phyrexian: HashMap<Color, usize>,
+    hybrid_phyrexian: HashMap<(Color, Color), usize>,
+    halves: HashMap<Option<Color>, usize>,
+}
+
+impl ManaCost {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if this cost has no symbols at all, as opposed to
+    /// a cost of `{0}`.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn symbols(&self) -> &[ManaSymbol] {
+        &self.symbols
+    }
+
+    /// Puts the symbols in the order they are printed on cards: `{X}`,
+    /// generic, snow and colorless mana first, then colored symbols
+    /// grouped by color in canonical order (see `ColorIdentity::ordered`).
+    /// Within a color, plain symbols come before hybrid, mono-hybrid and
+    /// Phyrexian ones.
+    pub fn sort(&mut self) {
+        use self::ManaSymbol::*;
+
+        let colors = self.colors();
+        self.symbols.sort_by_key(|&symbol| {
+            let color = |c| colors.position(c).unwrap_or(0);
+
+            match symbol {
+                Variable => (0, 0, 0),
+                Generic(_) => (1, 0, 0),
+                Half(None) => (1, 0, 1),
+                Snow => (2, 0, 0),
+                Colorless => (3, 0, 0),
+                Colored(c) => (4, color(c), 0),
+                Hybrid(c1, c2) => {
+                    let first = color(c1).min(color(c2));
+                    (4, first, 1)
+                }
+                MonoHybrid(c) => (4, color(c), 2),
+                Phyrexian(c) => (4, color(c), 3),
+                HybridPhyrexian(c1, c2) => {
+                    let first = color(c1).min(color(c2));
+                    (4, first, 4)
+                }
+                Half(Some(c)) => (4, color(c), 5),
+            }
+        });
+    }
+
+    /// The colors of this cost (202.2).
+    pub fn colors(&self) -> ColorIdentity {
+        self.symbols
+            .iter()
+            .flat_map(|symbol| symbol.colors().colors())
+            .collect()
+    }
+
+    /// The number of symbols in this cost that are `color` (700.5).
+    pub fn devotion(&self, color: Color) -> usize {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.colors().has(color))
+            .count()
+    }
+
+    /// The number of symbols in this cost that are any of `colors`. A
+    /// hybrid symbol of two of those colors is only counted once (700.5a).
+    pub fn devotion_to(&self, colors: ColorIdentity) -> usize {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.colors().colors().any(|c| colors.has(c)))
+            .count()
+    }
+
+    /// This cost plus `other`, as for cost increases and additional
+    /// costs (601.2f).
+    pub fn increase(&self, other: &ManaCost) -> ManaCost {
+        if self.is_empty() && other.is_empty() {
+            return ManaCost::new();
+        }
+
+        let mut res = ManaCostComparator::new(self);
+        res.add(&ManaCostComparator::new(other));
+        res.into_mana_cost()
+    }
+
+    /// This cost reduced by `reduction` (118.7, 601.2f). Generic mana in
+    /// the reduction only reduces the generic component. Colored mana
+    /// reduces a symbol of that color, or the generic component if the
+    /// cost has no such symbol. A cost can't be reduced below `{0}`.
+    pub fn reduce(&self, reduction: &ManaCost) -> ManaCost {
+        let mut res = ManaCostComparator::new(self);
+        for &symbol in reduction.symbols.iter() {
+            res.reduce(symbol);
+        }
+        res.into_mana_cost()
+    }
+
+    /// The total cost of a spell with this printed cost after applying
+    /// `modifiers` (601.2f). Increases are applied first, then
+    /// reductions, then effects like convoke and delve that pay for part
+    /// of the locked-in total.
+    pub fn apply_modifiers(&self, modifiers: &[CostModifier]) -> ManaCost {
+        let mut res = ManaCostComparator::new(self);
+
+        for modifier in modifiers.iter() {
+            if let CostModifier::Increase(cost) = modifier {
+                res.add(&ManaCostComparator::new(cost));
+            }
+        }
+
+        for modifier in modifiers.iter() {
+            for symbol in modifier.reduction() {
+                res.reduce(symbol);
+            }
+        }
+
+        for modifier in modifiers.iter() {
+            for symbol in modifier.payment() {
+                res.reduce(symbol);
+            }
+        }
+
+        res.into_mana_cost()
+    }
+}
+
+impl Add for ManaCost {
+    type Output = ManaCost;
+
+    fn add(self, other: ManaCost) -> ManaCost {
+        self.increase(&other)
+    }
+}
+
+impl Sub for ManaCost {
+    type Output = ManaCost;
+
+    fn sub(self, other: ManaCost) -> ManaCost {
+        self.reduce(&other)
+    }
+}
+
+fn take<K: Eq + Hash>(map: &mut HashMap<K, usize>, key: K) -> bool {
+    match map.get_mut(&key) {
+        Some(count) => {
+            *count -= 1;
+            if *count == 0 {
+                map.remove(&key);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+fn push_n(symbols: &mut Vec<ManaSymbol>, symbol: ManaSymbol, n: usize) {
+    symbols.resize(symbols.len() + n, symbol);
+}
+
+fn merge<K: Eq + Hash + Copy>(map: &mut HashMap<K, usize>, other: &HashMap<K, usize>) {
+    for (&key, &count) in other.iter() {
+        *map.entry(key).or_insert(0) += count;
+    }
+}
+
+impl ManaCostComparator {
+    fn new(mana_cost: &ManaCost) -> Self {
+        let mut res = ManaCostComparator {
+            ..Default::default()
+        };
+
+        for symbol in mana_cost.symbols.iter() {
+            use self::ManaSymbol::*;
+
+            match *symbol {
+                Generic(n) => res.generic += n as usize,
+                Colored(c) => *res.colors.entry(Some(c)).or_insert(0) += 1,
+                Colorless => *res.colors.entry(None).or_insert(0) += 1,
+                Variable => res.variable += 1,
+                Hybrid(c1, c2) => {
+                    let (c1, c2) = Color::color_pie_order(c1, c2);
+                    *res.hybrids.entry((c1, Some(c2))).or_insert(0) += 1;
+                }
+                MonoHybrid(c) => *res.hybrids.entry((c, None)).or_insert(0) += 1,
+                Phyrexian(c) => *res.phyrexian.entry(c).or_insert(0) += 1,
+                HybridPhyrexian(c1, c2) => {
+                    let key = Color::color_pie_order(c1, c2);
+                    *res.hybrid_phyrexian.entry(key).or_insert(0) += 1;
+                }
+                Half(c) => *res.halves.entry(c).or_insert(0) += 1,
+                Snow => res.snow += 1,
+            }
+        }
+
+        res
+    }
+
+    fn add(&mut self, other: &ManaCostComparator) {
+        self.generic += other.generic;
+        self.variable += other.variable;
+        self.snow += other.snow;
+        merge(&mut self.colors, &other.colors);
+        merge(&mut self.hybrids, &other.hybrids);
+        merge(&mut self.phyrexian, &other.phyrexian);
+        merge(&mut self.hybrid_phyrexian, &other.hybrid_phyrexian);
+        merge(&mut self.halves, &other.halves);
+    }
+
+    fn reduce_generic(&mut self, amount: usize) {
+        self.generic = self.generic.saturating_sub(amount);
+    }
+
+    fn reduce_color(&mut self, color: Color) {
+        let hybrid = Color::iterator()
+            .map(|other| Color::color_pie_order(color, other))
+            .find(|&(c1, c2)| c1 != c2 && self.hybrids.contains_key(&(c1, Some(c2))));
+
+        let hybrid_phyrexian = self
+            .hybrid_phyrexian
+            .keys()
+            .find(|&&(c1, c2)| c1 == color || c2 == color)
+            .cloned();
+
+        let reduced = take(&mut self.colors, Some(color))
+            || hybrid.is_some_and(|(c1, c2)| take(&mut self.hybrids, (c1, Some(c2))))
+            || take(&mut self.phyrexian, color)
+            || hybrid_phyrexian.is_some_and(|key| take(&mut self.hybrid_phyrexian, key))
+            || take(&mut self.hybrids, (color, None));
+
+        if !reduced {
+            self.reduce_generic(1);
+        }
+    }
+
+    fn reduce(&mut self, symbol: ManaSymbol) {
+        use self::ManaSymbol::*;
+
+        match symbol {
+            Generic(n) => self.reduce_generic(n as usize),
+            Colored(c) | Phyrexian(c) | MonoHybrid(c) => self.reduce_color(c),
+            Hybrid(c1, c2) | HybridPhyrexian(c1, c2) => {
+                if self.colors.contains_key(&Some(c1)) || !self.colors.contains_key(&Some(c2)) {
+                    self.reduce_color(c1)
+                } else {
+                    self.reduce_color(c2)
+                }
+            }
+            Colorless => {
+                if !take(&mut self.colors, None) {
+                    self.reduce_generic(1);
+                }
+            }
+            Snow => {
+                if self.snow > 0 {
+                    self.snow -= 1;
+                } else {
+                    self.reduce_generic(1);
+                }
+            }
+            Variable | Half(_) => (),
+        }
+    }
+
+    /// Rebuilds a cost from the buckets. An otherwise empty cost is
+    /// `{0}`, since a reduced cost can't be less than that (601.2f).
+    fn into_mana_cost(self) -> ManaCost {
+        use self::ManaSymbol::*;
+
+        let mut symbols = vec![Variable; self.variable];
+
+        if self.generic > 0 {
+            symbols.push(Generic(self.generic.min(u32::MAX as usize) as u32));
+        }
+        push_n(
+            &mut symbols,
+            Half(None),
+            self.halves.get(&None).cloned().unwrap_or(0),
+        );
+        push_n(&mut symbols, Snow, self.snow);
+        push_n(
+            &mut symbols,
+            Colorless,
+            self.colors.get(&None).cloned().unwrap_or(0),
+        );
+
+        for c1 in Color::iterator() {
+            for c2 in Color::iterator() {
+                if let Some(&n) = self.hybrids.get(&(c1, Some(c2))) {
+                    push_n(&mut symbols, Hybrid(c1, c2), n);
+                }
+            }
+        }
+
+        for c in Color::iterator() {
+            let mono = self.hybrids.get(&(c, None)).cloned().unwrap_or(0);
+            push_n(&mut symbols, MonoHybrid(c), mono);
+        }
+
+        for c in Color::iterator() {
+            let colored = self.colors.get(&Some(c)).cloned().unwrap_or(0);
+            push_n(&mut symbols, Colored(c), colored);
+            let phyrexian = self.phyrexian.get(&c).cloned().unwrap_or(0);
+            push_n(&mut symbols, Phyrexian(c), phyrexian);
+            let half = self.halves.get(&Some(c)).cloned().unwrap_or(0);
+            push_n(&mut symbols, Half(Some(c)), half);
+        }
+
+        for c1 in Color::iterator() {
+            for c2 in Color::iterator() {
+                if let Some(&n) = self.hybrid_phyrexian.get(&(c1, c2)) {
+                    push_n(&mut symbols, HybridPhyrexian(c1, c2), n);
+                }
+            }
+        }
+
+        if symbols.is_empty() {
+            symbols.push(Generic(0));
+        }
+
+        let mut cost = ManaCost { symbols };
+        cost.sort();
+        cost
+    }
+}
+
+impl PartialEq for ManaCost {
+    fn eq(&self, other: &Self) -> bool {
+        ManaCostComparator::new(self) == ManaCostComparator::new(other)
+    }
+}
+
+impl Eq for ManaCost {}
+
+impl FromIterator<ManaSymbol> for ManaCost {
+    fn from_iter<T>(iter: T) -> ManaCost
+    where
+        T: IntoIterator<Item = ManaSymbol>,
+    {
+        ManaCost {
+            symbols: Vec::from_iter(iter),
+        }
+    }
+}
+
+impl fmt::Display for ManaCost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.symbols.iter().try_for_each(|s| write!(f, "{}", s))
+    }
+}
+
+impl FromStr for ManaCost {
+    type Err = ParseManaCostError;
+
+    fn from_str(s: &str) -> Result<ManaCost, ParseManaCostError> {
+        symbols(s).map(|res| res.map(|(_, sym)| sym)).collect()
+    }
+}
+
+impl_deserialize_with_fromstr!(ManaCost);
+impl_serialize_with_tostring!(ManaCost);
+
+impl ConvertedManaCost for ManaCost {
+    /// Half-mana symbols add ½ each, and the total is rounded down.
+    fn converted_mana_cost(&self) -> usize {
+        let halves = self
+            .symbols
+            .iter()
+            .filter(|s| matches!(s, ManaSymbol::Half(_)))
+            .count();
+        self.symbols
+            .iter()
+            .map(|s| s.converted_mana_cost())
+            .sum::<usize>()
+            + halves / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use self::Color::*;
+    use self::ManaSymbol::*;
+    use super::*;
+
+    fn cost(s: &str) -> ManaCost {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn mana_symbol_to_string() {
+        assert_eq!(Phyrexian(Blue).to_string(), "{U/P}");
+        assert_eq!(Generic(0).to_string(), "{0}");
+    }
+
+    #[test]
+    fn mana_cost_to_string() {
+        let cost = ManaCost::from_iter(vec![
+            ManaSymbol::Generic(5),
+            ManaSymbol::Colorless,
+            ManaSymbol::Colored(Green),
+            ManaSymbol::Hybrid(Black, White),
+        ]);
+
+        assert_eq!(cost.to_string(), "{5}{C}{G}{W/B}");
+    }
+
+    #[test]
+    fn mana_symbol_parse() {
+        #[rustfmt::skip]
+        let pairs = [
+            ("{0}", Generic(0)),
+            ("{15}", Generic(15)),
+            ("{W}", Colored(White)),
+            ("{C}", Colorless),
+            ("{X}", Variable),
+            ("{S}", Snow),
+            ("{W/U}", Hybrid(White, Blue)),
+            ("{U/W}", Hybrid(Blue, White)),
+            ("{2/R}", MonoHybrid(Red)),
+            ("{R/2}", MonoHybrid(Red)),
+            ("{G/P}", Phyrexian(Green)),
+            ("{G/U/P}", HybridPhyrexian(Green, Blue)),
+            ("{HW}", Half(Some(White))),
+            ("{\u{bd}}", Half(None)),
+            ("{1000000}", Generic(1_000_000)),
+        ];
+
+        for (string, symbol) in pairs.iter() {
+            assert_eq!(string.parse::<ManaSymbol>().unwrap(), *symbol);
+        }
+
+        assert!("{G/G/P}".parse::<ManaSymbol>().is_err());
+        assert!("{G/U/2}".parse::<ManaSymbol>().is_err());
+    }
+
+    #[test]
+    fn unusual_mana_costs() {
+        // Tamiyo, Compleated Sage; Little Girl; Gleemax.
+        let tamiyo = cost("{2}{G}{G/U/P}{U}");
+        assert_eq!(tamiyo.to_string(), "{2}{G}{G/U/P}{U}");
+        assert_eq!(tamiyo.converted_mana_cost(), 5);
+        assert_eq!(tamiyo.colors(), "GU".parse().unwrap());
+        assert_eq!(tamiyo.devotion(Green), 2);
+
+        assert_eq!(cost("{HW}").converted_mana_cost(), 0);
+        assert_eq!(cost("{HW}").colors(), "W".parse().unwrap());
+        assert_eq!(cost("{\u{bd}}{\u{bd}}{HR}").converted_mana_cost(), 1);
+        assert_eq!(cost("{1000000}").converted_mana_cost(), 1_000_000);
+
+        assert_eq!(cost("{1}{G/U/P}") + cost("{HW}"), cost("{1}{HW}{U/G/P}"));
+        assert_eq!(cost("{1}{G/U/P}") - cost("{U}"), cost("{1}"));
+    }
+
+    #[test]
+    fn mana_cost_round_trip() {
+        let costs = [
+            "",
+            "{0}",
+            "{2}{W/U}{G/P}{2/R}{X}{S}{C}",
+            "{X}{X}{R}",
+            "{1}{B/G}{B/G}",
+            "{16}",
+        ];
+
+        for string in costs.iter() {
+            let cost = string.parse::<ManaCost>().unwrap();
+            assert_eq!(cost.to_string(), *string);
+        }
+    }
+
+    #[test]
+    fn mana_cost_parse_errors() {
+        let cases = [
+            ("{2}{Q}", ParseManaCostErrorKind::UnknownSymbol, 3, "{Q}"),
+            ("{W/W}", ParseManaCostErrorKind::UnknownSymbol, 0, "{W/W}"),
+            ("{1}{G", ParseManaCostErrorKind::Unclosed, 3, "{G"),
+            ("{1}G{G}", ParseManaCostErrorKind::UnexpectedText, 3, "G"),
+            (
+                "{9999999999}",
+                ParseManaCostErrorKind::UnknownSymbol,
+                0,
+                "{9999999999}",
+            ),
+        ];
+
+        for (string, kind, offset, symbol) in cases.iter() {
+            let err = string.parse::<ManaCost>().unwrap_err();
+            assert_eq!(err.kind(), kind);
+            assert_eq!(err.offset(), *offset);
+            assert_eq!(err.symbol(), *symbol);
+        }
+
+        assert_eq!(
+            "{2}{Q}".parse::<ManaCost>().unwrap_err().to_string(),
+            "unknown mana symbol `{Q}` at byte 3"
+        );
+    }
+
+    #[test]
+    fn mana_cost_increase() {
+        assert_eq!(cost("{1}{U}") + cost("{2}"), cost("{3}{U}"));
+        assert_eq!(cost("{W}") + cost("{W/U}{B/P}"), cost("{W}{W/U}{B/P}"));
+        assert_eq!(cost("") + cost(""), cost(""));
+        assert!((cost("") + cost("")).is_empty());
+    }
+
+    #[test]
+    fn mana_cost_reduce() {
+        #[rustfmt::skip]
+        let cases = [
+            ("{3}{R}", "{1}", "{2}{R}"),
+            ("{1}{R}", "{2}", "{R}"),
+            ("{R}{R}", "{3}", "{R}{R}"),
+            ("{2}{W}{U}", "{W}", "{2}{U}"),
+            ("{2}{U}", "{W}", "{1}{U}"),
+            ("{U}", "{W}", "{U}"),
+            ("{1}{G/W}", "{W}", "{1}"),
+            ("{B/P}{B/P}", "{B}", "{B/P}"),
+            ("{8}{C}{C}", "{C}{C}{C}", "{7}"),
+            ("{X}{R}", "{1}{R}", "{X}"),
+            ("{2}", "{4}", "{0}"),
+        ];
+
+        for (printed, reduction, expected) in cases.iter() {
+            let reduced = cost(printed) - cost(reduction);
+            assert_eq!(reduced, cost(expected), "{} - {}", printed, reduction);
+            assert_eq!(reduced.to_string(), *expected);
+        }
+    }
+
+    #[test]
+    fn mana_cost_modifiers() {
+        // Thoughtcast with two artifacts.
+        let total = cost("{4}{U}").apply_modifiers(&[CostModifier::Affinity(2)]);
+        assert_eq!(total, cost("{2}{U}"));
+
+        // Reductions apply after increases, so {1} more and {2} less on a
+        // one-drop still leaves the colored mana.
+        let total = cost("{G}").apply_modifiers(&[
+            CostModifier::Reduce(cost("{2}")),
+            CostModifier::Increase(cost("{1}")),
+        ]);
+        assert_eq!(total, cost("{G}"));
+
+        // Stoke the Flames convoked with a red and a green creature.
+        let total = cost("{2}{R}{R}")
+            .apply_modifiers(&[CostModifier::Convoke(vec![Some(Red), Some(Green)])]);
+        assert_eq!(total, cost("{1}{R}"));
+
+        // Treasure Cruise delving five cards.
+        let total = cost("{7}{U}").apply_modifiers(&[CostModifier::Delve(5)]);
+        assert_eq!(total, cost("{2}{U}"));
+
+        let total = cost("{2}{U}").apply_modifiers(&[CostModifier::Improvise(3)]);
+        assert_eq!(total, cost("{U}"));
+    }
+
+    #[test]
+    fn mana_cost_colors() {
+        assert_eq!(cost("{3}{C}{S}").colors().to_string(), "C");
+        assert_eq!(
+            cost("{1}{W}{W}").colors(),
+            [White].iter().cloned().collect()
+        );
+        assert_eq!(
+            cost("{2/W}{U/P}{B/R}").colors(),
+            [White, Blue, Black, Red].iter().cloned().collect()
+        );
+    }
+
+    #[test]
+    fn mana_cost_devotion() {
+        // Every symbol that is black counts, whatever else it is (700.5).
+        let cost = cost("{1}{B}{B/G}{2/B}{B/P}");
+        assert_eq!(cost.devotion(Black), 4);
+        assert_eq!(cost.devotion(Green), 1);
+        assert_eq!(cost.devotion(White), 0);
+
+        let black_green = [Black, Green].iter().cloned().collect();
+        assert_eq!(cost.devotion_to(black_green), 4);
+        assert_eq!(
+            self::cost("{W}{U}{W/U}").devotion_to(ColorIdentity::new()),
+            0
+        );
+        assert_eq!(
+            self::cost("{W}{U}{W/U}").devotion_to([White, Blue].iter().cloned().collect()),
+            3
+        );
+    }
+
+    #[test]
+    fn mana_cost_sort() {
+        #[rustfmt::skip]
+        let pairs = [
+            ("{W}{G}{1}", "{1}{G}{W}"),
+            ("{U}{R}{G}", "{G}{U}{R}"),
+            ("{W/G}{W/G}{1}", "{1}{G/W}{G/W}"),
+            ("{W}{G/W}{G}{1}", "{1}{G}{G/W}{W}"),
+            ("{B/P}{4}{B}", "{4}{B}{B/P}"),
+            ("{C}{C}{8}", "{8}{C}{C}"),
+            ("{R}{X}{X}", "{X}{X}{R}"),
+            ("{2/G}{2/R}{2/B}{2/U}{2/W}", "{2/W}{2/U}{2/B}{2/R}{2/G}"),
+        ];
+
+        for (unsorted, sorted) in pairs.iter() {
+            let mut cost = cost(unsorted);
+            cost.sort();
+            assert_eq!(cost.to_string(), *sorted);
+        }
+
+        assert_eq!((cost("{R}") + cost("{W}{1}")).to_string(), "{1}{R}{W}");
+    }
+
+    #[test]
+    fn mana_cost_serde() {
+        let cost: ManaCost = serde_json::from_str(r#""{3}{U}{U}""#).unwrap();
+        assert_eq!(cost, "{U}{3}{U}".parse().unwrap());
+        assert_eq!(serde_json::to_string(&cost).unwrap(), r#""{3}{U}{U}""#);
+        assert!(serde_json::from_str::<ManaCost>(r#""{3}{V}""#).is_err());
+    }
+}
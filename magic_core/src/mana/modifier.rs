@@ -0,0 +1,49 @@
+use super::{ManaCost, ManaSymbol};
+use crate::Color;
+
+/// Something that changes how much mana must be spent to cast a spell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CostModifier {
+    /// "Costs {2} more", kicker and other additional costs.
+    Increase(ManaCost),
+    /// "Costs {1} less" or "costs {W} less".
+    Reduce(ManaCost),
+    /// Affinity, with the number of matching permanents (702.41).
+    Affinity(u16),
+    /// Convoke, with the color chosen for each tapped creature, or `None`
+    /// if it pays for generic mana (702.51).
+    Convoke(Vec<Option<Color>>),
+    /// Delve, with the number of cards exiled (702.66).
+    Delve(u16),
+    /// Improvise, with the number of artifacts tapped (702.126).
+    Improvise(u16),
+}
+
+impl CostModifier {
+    /// The symbols removed from the total cost as a cost reduction.
+    pub(super) fn reduction(&self) -> Vec<ManaSymbol> {
+        match self {
+            CostModifier::Reduce(cost) => cost.symbols.clone(),
+            CostModifier::Affinity(n) => vec![ManaSymbol::Generic((*n).into())],
+            _ => vec![],
+        }
+    }
+
+    /// The symbols paid for by something other than mana once the total
+    /// cost has been locked in.
+    pub(super) fn payment(&self) -> Vec<ManaSymbol> {
+        match self {
+            CostModifier::Convoke(colors) => colors
+                .iter()
+                .map(|color| match color {
+                    Some(c) => ManaSymbol::Colored(*c),
+                    None => ManaSymbol::Generic(1),
+                })
+                .collect(),
+            CostModifier::Delve(n) | CostModifier::Improvise(n) => {
+                vec![ManaSymbol::Generic((*n).into())]
+            }
+            _ => vec![],
+        }
+    }
+}
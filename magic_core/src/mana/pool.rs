@@ -0,0 +1,625 @@
+use indexmap::IndexMap;
+use std::iter::FromIterator;
+
+use super::{ManaCost, ManaSymbol};
+use crate::card::TypeLine;
+use crate::phase::{EndingStep, Step};
+use crate::type_::Type;
+use crate::Color;
+
+/// A restriction on what a unit of mana can be spent on (106.6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManaRestriction {
+    /// "Spend this mana only to cast spells."
+    Spells,
+    /// "Spend this mana only to cast creature spells," and so on.
+    SpellsWithType(Type),
+    /// "Spend this mana only to activate abilities."
+    Abilities,
+    /// "This mana can't be spent to pay generic mana costs."
+    NotGeneric,
+}
+
+/// When a unit of mana leaves its player's mana pool (106.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManaExpiry {
+    /// Empties as each step and phase ends. This is normal mana.
+    EndOfStep,
+    /// Stays until the given step ends, e.g. "until end of turn, you
+    /// don't lose this mana as steps and phases end."
+    EndOf(Step),
+    /// Never empties, as with Upwelling.
+    Never,
+}
+
+impl ManaExpiry {
+    /// "Until end of turn" mana, which empties at the end of the cleanup
+    /// step.
+    pub fn end_of_turn() -> ManaExpiry {
+        ManaExpiry::EndOf(Step::Ending(EndingStep::Cleanup))
+    }
+
+    fn expires_at(self, ending: Step) -> bool {
+        match self {
+            ManaExpiry::EndOfStep => true,
+            ManaExpiry::EndOf(step) => step == ending,
+            ManaExpiry::Never => false,
+        }
+    }
+}
+
+/// What mana is being spent on, for checking restrictions.
+#[derive(Debug, Clone, Copy)]
+pub enum SpendTarget<'a> {
+    /// Casting a spell with the given type line.
+    Spell(&'a TypeLine),
+    /// Activating an ability.
+    Ability,
+    /// Any other cost, such as a ward or triggered ability cost.
+    Other,
+}
+
+/// A single unit of mana (106.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mana {
+    color: Option<Color>,
+    snow: bool,
+    restriction: Option<ManaRestriction>,
+    expiry: ManaExpiry,
+}
+
+impl Mana {
+    /// Mana of the given color, or colorless mana if `color` is `None`.
+    pub fn new(color: Option<Color>) -> Mana {
+        Mana {
+            color,
+            snow: false,
+            restriction: None,
+            expiry: ManaExpiry::EndOfStep,
+        }
+    }
+
+    pub fn colored(color: Color) -> Mana {
+        Mana::new(Some(color))
+    }
+
+    pub fn colorless() -> Mana {
+        Mana::new(None)
+    }
+
+    /// The same mana, produced by a snow source (106.3).
+    pub fn snow(self) -> Mana {
+        Mana { snow: true, ..self }
+    }
+
+    pub fn color(self) -> Option<Color> {
+        self.color
+    }
+
+    pub fn is_snow(self) -> bool {
+        self.snow
+    }
+
+    /// The same mana, with a restriction on what it can be spent on.
+    pub fn restricted(self, restriction: ManaRestriction) -> Mana {
+        Mana {
+            restriction: Some(restriction),
+            ..self
+        }
+    }
+
+    /// The same mana, lasting until `expiry`.
+    pub fn lasting(self, expiry: ManaExpiry) -> Mana {
+        Mana { expiry, ..self }
+    }
+
+    pub fn restriction(self) -> Option<ManaRestriction> {
+        self.restriction
+    }
+
+    pub fn expiry(self) -> ManaExpiry {
+        self.expiry
+    }
+
+    /// Whether this mana may be spent on `target` to pay `requirement`.
+    fn can_spend(self, target: SpendTarget, requirement: Requirement) -> bool {
+        match (self.restriction, target) {
+            (None, _) => true,
+            (Some(ManaRestriction::Spells), SpendTarget::Spell(_)) => true,
+            (Some(ManaRestriction::SpellsWithType(type_)), SpendTarget::Spell(type_line)) => {
+                type_line.has_type(type_)
+            }
+            (Some(ManaRestriction::Abilities), SpendTarget::Ability) => true,
+            (Some(ManaRestriction::NotGeneric), _) => requirement != Requirement::Generic,
+            _ => false,
+        }
+    }
+}
+
+/// A player's mana pool (106.4).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManaPool {
+    mana: IndexMap<Mana, u32>,
+}
+
+impl ManaPool {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn add(&mut self, mana: Mana, amount: u32) {
+        if amount > 0 {
+            *self.mana.entry(mana).or_insert(0) += amount;
+        }
+    }
+
+    /// Removes `amount` of `mana`, returning `false` and leaving the pool
+    /// unchanged if there is not enough.
+    pub fn remove(&mut self, mana: Mana, amount: u32) -> bool {
+        match self.mana.get_mut(&mana) {
+            Some(count) if *count >= amount => {
+                *count -= amount;
+                if *count == 0 {
+                    self.mana.shift_remove(&mana);
+                }
+                true
+            }
+            _ => amount == 0,
+        }
+    }
+
+    pub fn count(&self, mana: Mana) -> u32 {
+        self.mana.get(&mana).cloned().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.mana.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mana.is_empty()
+    }
+
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (Mana, u32)> + 'a {
+        self.mana.iter().map(|(&mana, &count)| (mana, count))
+    }
+
+    pub fn clear(&mut self) {
+        self.mana.clear();
+    }
+
+    /// Empties the pool as `ending` ends, keeping mana that lasts longer
+    /// (106.4, 500.4).
+    pub fn empty(&mut self, ending: Step) {
+        self.empty_where(ending, |_| false);
+    }
+
+    /// Like `empty`, but also keeps mana for which `persists` returns
+    /// `true`, as with Omnath, Locus of Mana keeping green mana.
+    pub fn empty_where(&mut self, ending: Step, persists: impl Fn(Mana) -> bool) {
+        self.mana
+            .retain(|&mana, _| !mana.expiry.expires_at(ending) || persists(mana));
+    }
+
+    /// Every distinct way this pool can pay `cost`. Two payments are
+    /// distinct if they spend different mana or different amounts of life.
+    ///
+    /// Restricted mana is only spent if it can be spent on anything; use
+    /// `payments_for` to say what the cost is for. `{X}` is treated as
+    /// zero; to pay for a chosen X, add the corresponding generic mana to
+    /// the cost first.
+    pub fn payments(&self, cost: &ManaCost) -> Vec<Payment> {
+        self.payments_for(cost, SpendTarget::Other)
+    }
+
+    /// Every distinct way this pool can pay `cost` when spending mana on
+    /// `target`, honouring each unit's restriction.
+    pub fn payments_for(&self, cost: &ManaCost, target: SpendTarget) -> Vec<Payment> {
+        let keys: Vec<Mana> = self.mana.keys().cloned().collect();
+        let available: Vec<u32> = self.mana.values().cloned().collect();
+
+        let mut found: Vec<(Vec<u32>, u32)> = Vec::new();
+
+        for (mut requirements, life) in alternatives(cost) {
+            if requirements.len() > self.total() as usize {
+                continue;
+            }
+
+            requirements.sort_by_key(|req| req.rank());
+
+            let mut search = Search {
+                keys: &keys,
+                available: available.clone(),
+                spent: vec![0; keys.len()],
+                life,
+                target,
+                found: &mut found,
+            };
+            search.run(&requirements, None, 0);
+        }
+
+        let mut payments: Vec<Payment> = found
+            .into_iter()
+            .map(|(spent, life)| Payment {
+                mana: keys.iter().cloned().zip(spent).collect_pool(),
+                life,
+            })
+            .collect();
+
+        payments.sort_by_key(Payment::sort_key);
+        payments
+    }
+
+    /// The payment that costs the least life, then the least mana, then
+    /// spends the fewest persistent, unrestricted, snow and colored mana.
+    pub fn cheapest_payment(&self, cost: &ManaCost) -> Option<Payment> {
+        self.payments(cost).into_iter().next()
+    }
+
+    pub fn cheapest_payment_for(&self, cost: &ManaCost, target: SpendTarget) -> Option<Payment> {
+        self.payments_for(cost, target).into_iter().next()
+    }
+
+    pub fn can_pay(&self, cost: &ManaCost) -> bool {
+        !self.payments(cost).is_empty()
+    }
+
+    pub fn can_pay_for(&self, cost: &ManaCost, target: SpendTarget) -> bool {
+        !self.payments_for(cost, target).is_empty()
+    }
+
+    /// Removes the mana spent by `payment` from this pool, returning
+    /// `false` and leaving the pool unchanged if it cannot be afforded.
+    pub fn pay(&mut self, payment: &Payment) -> bool {
+        if payment
+            .mana
+            .iter()
+            .any(|(mana, count)| self.count(mana) < count)
+        {
+            return false;
+        }
+
+        for (mana, count) in payment.mana.iter() {
+            self.remove(mana, count);
+        }
+
+        true
+    }
+}
+
+impl FromIterator<Mana> for ManaPool {
+    fn from_iter<T: IntoIterator<Item = Mana>>(iter: T) -> ManaPool {
+        let mut pool = ManaPool::new();
+        for mana in iter {
+            pool.add(mana, 1);
+        }
+        pool
+    }
+}
+
+trait CollectPool {
+    fn collect_pool(self) -> ManaPool;
+}
+
+impl<I: Iterator<Item = (Mana, u32)>> CollectPool for I {
+    fn collect_pool(self) -> ManaPool {
+        let mut pool = ManaPool::new();
+        for (mana, amount) in self {
+            pool.add(mana, amount);
+        }
+        pool
+    }
+}
+
+/// One way of paying a mana cost: the mana spent and the life paid for
+/// Phyrexian symbols (107.4f).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payment {
+    mana: ManaPool,
+    life: u32,
+}
+
+impl Payment {
+    pub fn mana(&self) -> &ManaPool {
+        &self.mana
+    }
+
+    pub fn life(&self) -> u32 {
+        self.life
+    }
+
+    fn sort_key(&self) -> (u32, u32, u32, u32, u32, u32) {
+        let count = |pred: &dyn Fn(Mana) -> bool| -> u32 {
+            self.mana
+                .iter()
+                .filter(|&(m, _)| pred(m))
+                .map(|(_, n)| n)
+                .sum()
+        };
+
+        (
+            self.life,
+            self.mana.total(),
+            count(&|m| m.expiry != ManaExpiry::EndOfStep),
+            count(&|m| m.restriction.is_none()),
+            count(&Mana::is_snow),
+            count(&|m| m.color.is_some()),
+        )
+    }
+}
+
+/// What a single unit of mana must be to pay part of a cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Requirement {
+    Color(Option<Color>),
+    Snow,
+    Generic,
+}
+
+impl Requirement {
+    fn satisfied_by(self, mana: Mana, target: SpendTarget) -> bool {
+        let matches = match self {
+            Requirement::Color(color) => mana.color == color,
+            Requirement::Snow => mana.snow,
+            Requirement::Generic => true,
+        };
+
+        matches && mana.can_spend(target, self)
+    }
+
+    /// Most restrictive requirements are satisfied first.
+    fn rank(self) -> u8 {
+        match self {
+            Requirement::Color(_) => 0,
+            Requirement::Snow => 1,
+            Requirement::Generic => 2,
+        }
+    }
+}
+
+/// The ways a single symbol can be paid, as requirements plus life.
+fn symbol_alternatives(symbol: ManaSymbol) -> Vec<(Vec<Requirement>, u32)> {
+    use self::ManaSymbol::*;
+
+    match symbol {
+        Generic(n) => vec![(vec![Requirement::Generic; n as usize], 0)],
+        Colored(c) => vec![(vec![Requirement::Color(Some(c))], 0)],
+        Colorless => vec![(vec![Requirement::Color(None)], 0)],
+        Variable => vec![(vec![], 0)],
+        Hybrid(c1, c2) => vec![
+            (vec![Requirement::Color(Some(c1))], 0),
+            (vec![Requirement::Color(Some(c2))], 0),
+        ],
+        MonoHybrid(c) => vec![
+            (vec![Requirement::Color(Some(c))], 0),
+            (vec![Requirement::Generic; 2], 0),
+        ],
+        Phyrexian(c) => vec![(vec![Requirement::Color(Some(c))], 0), (vec![], 2)],
+        HybridPhyrexian(c1, c2) => vec![
+            (vec![Requirement::Color(Some(c1))], 0),
+            (vec![Requirement::Color(Some(c2))], 0),
+            (vec![], 2),
+        ],
+        // A pool holds whole mana, so half a mana costs a whole one.
+        Half(c) => vec![(
+            vec![c.map_or(Requirement::Generic, |c| Requirement::Color(Some(c)))],
+            0,
+        )],
+        Snow => vec![(vec![Requirement::Snow], 0)],
+    }
+}
+
+/// Every combination of choices for the hybrid and Phyrexian symbols
+/// in `cost`.
+fn alternatives(cost: &ManaCost) -> Vec<(Vec<Requirement>, u32)> {
+    cost.symbols.iter().fold(vec![(vec![], 0)], |acc, &symbol| {
+        let choices = symbol_alternatives(symbol);
+        acc.iter()
+            .flat_map(|(reqs, life)| {
+                choices.iter().map(move |(more, extra)| {
+                    let mut reqs = reqs.clone();
+                    reqs.extend(more.iter().cloned());
+                    (reqs, life + extra)
+                })
+            })
+            .collect()
+    })
+}
+
+struct Search<'a> {
+    keys: &'a [Mana],
+    available: Vec<u32>,
+    spent: Vec<u32>,
+    life: u32,
+    target: SpendTarget<'a>,
+    found: &'a mut Vec<(Vec<u32>, u32)>,
+}
+
+impl<'a> Search<'a> {
+    /// Assigns mana to each remaining requirement in turn. Identical
+    /// consecutive requirements only consider mana at or after the
+    /// previous choice, so each multiset of spent mana is visited once.
+    fn run(&mut self, requirements: &[Requirement], prev: Option<Requirement>, min_index: usize) {
+        let (&req, rest) = match requirements.split_first() {
+            Some(split) => split,
+            None => {
+                let result = (self.spent.clone(), self.life);
+                if !self.found.contains(&result) {
+                    self.found.push(result);
+                }
+                return;
+            }
+        };
+
+        let start = if prev == Some(req) { min_index } else { 0 };
+
+        for i in start..self.keys.len() {
+            if self.available[i] == 0 || !req.satisfied_by(self.keys[i], self.target) {
+                continue;
+            }
+
+            self.available[i] -= 1;
+            self.spent[i] += 1;
+            self.run(rest, Some(req), i);
+            self.available[i] += 1;
+            self.spent[i] -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color::*;
+
+    fn pool(mana: &[(Mana, u32)]) -> ManaPool {
+        mana.iter().cloned().collect_pool()
+    }
+
+    fn cost(s: &str) -> ManaCost {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn pay_colored_and_generic() {
+        let pool = pool(&[(Mana::colored(Green), 2), (Mana::colored(Red), 1)]);
+
+        assert!(pool.can_pay(&cost("{1}{G}")));
+        assert!(pool.can_pay(&cost("{G}{G}{R}")));
+        assert!(!pool.can_pay(&cost("{R}{R}")));
+        assert!(!pool.can_pay(&cost("{4}")));
+
+        // {G} then either the other green or the red for {1}.
+        assert_eq!(pool.payments(&cost("{1}{G}")).len(), 2);
+    }
+
+    #[test]
+    fn pay_colorless() {
+        let pool = pool(&[(Mana::colored(Blue), 3)]);
+        assert!(!pool.can_pay(&cost("{C}")));
+
+        let pool = self::pool(&[(Mana::colorless(), 1), (Mana::colored(Blue), 1)]);
+        assert!(pool.can_pay(&cost("{C}{U}")));
+        assert!(pool.can_pay(&cost("{2}")));
+    }
+
+    #[test]
+    fn pay_hybrid() {
+        let pool = pool(&[(Mana::colored(White), 1), (Mana::colored(Blue), 1)]);
+
+        let payments = pool.payments(&cost("{W/U}"));
+        assert_eq!(payments.len(), 2);
+        assert!(pool.can_pay(&cost("{W/U}{W/U}")));
+        assert!(!pool.can_pay(&cost("{W/B}{W/B}")));
+    }
+
+    #[test]
+    fn pay_mono_hybrid() {
+        let pool = pool(&[(Mana::colorless(), 2), (Mana::colored(White), 1)]);
+
+        assert!(pool.can_pay(&cost("{2/W}{2/W}")));
+        assert!(!pool.can_pay(&cost("{2/W}{2/W}{2/W}")));
+
+        let cheapest = pool.cheapest_payment(&cost("{2/W}")).unwrap();
+        assert_eq!(cheapest.mana().total(), 1);
+        assert_eq!(cheapest.mana().count(Mana::colored(White)), 1);
+    }
+
+    #[test]
+    fn pay_phyrexian() {
+        let pool = pool(&[(Mana::colored(Black), 2)]);
+
+        let payments = pool.payments(&cost("{1}{B/P}{B/P}"));
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[0].life(), 2);
+        assert_eq!(payments[1].life(), 4);
+
+        let empty = ManaPool::new();
+        let payment = empty.cheapest_payment(&cost("{G/P}")).unwrap();
+        assert!(payment.mana().is_empty());
+        assert_eq!(payment.life(), 2);
+    }
+
+    #[test]
+    fn pay_snow() {
+        let pool = pool(&[(Mana::colored(Green).snow(), 1), (Mana::colored(Green), 1)]);
+
+        assert!(pool.can_pay(&cost("{S}{G}")));
+        assert!(!pool.can_pay(&cost("{S}{S}")));
+
+        let payment = pool.cheapest_payment(&cost("{G}")).unwrap();
+        assert_eq!(payment.mana().count(Mana::colored(Green)), 1);
+    }
+
+    #[test]
+    fn pay_removes_mana() {
+        let mut pool = pool(&[(Mana::colored(Red), 2), (Mana::colorless(), 1)]);
+
+        let payment = pool.cheapest_payment(&cost("{1}{R}")).unwrap();
+        assert!(pool.pay(&payment));
+        assert_eq!(pool.total(), 1);
+        assert!(!pool.pay(&payment));
+        assert_eq!(pool.total(), 1);
+    }
+
+    #[test]
+    fn pay_restricted() {
+        let creature_mana =
+            Mana::colored(Green).restricted(ManaRestriction::SpellsWithType(Type::Creature));
+        let pool = pool(&[(creature_mana, 2), (Mana::colored(Green), 1)]);
+
+        let creature = crate::type_line!(Creature; Elf);
+        let sorcery = crate::type_line!(Sorcery);
+
+        assert!(pool.can_pay_for(&cost("{2}{G}"), SpendTarget::Spell(&creature)));
+        assert!(!pool.can_pay_for(&cost("{2}{G}"), SpendTarget::Spell(&sorcery)));
+        assert!(pool.can_pay_for(&cost("{G}"), SpendTarget::Spell(&sorcery)));
+        assert!(!pool.can_pay_for(&cost("{1}{G}"), SpendTarget::Ability));
+        assert!(!pool.can_pay(&cost("{1}{G}")));
+
+        // The restricted mana is spent before the unrestricted mana.
+        let payment = pool
+            .cheapest_payment_for(&cost("{G}"), SpendTarget::Spell(&creature))
+            .unwrap();
+        assert_eq!(payment.mana().count(creature_mana), 1);
+    }
+
+    #[test]
+    fn pay_not_generic() {
+        let mana = Mana::colorless().restricted(ManaRestriction::NotGeneric);
+        let pool = pool(&[(mana, 2)]);
+
+        assert!(pool.can_pay(&cost("{C}{C}")));
+        assert!(!pool.can_pay(&cost("{1}{C}")));
+        assert!(!pool.can_pay(&cost("{2/W}")));
+    }
+
+    #[test]
+    fn empty_pool() {
+        use crate::phase::{BeginningStep, Step};
+
+        let upkeep = Step::Beginning(BeginningStep::Upkeep);
+        let draw = Step::Beginning(BeginningStep::Draw);
+
+        let mut pool = pool(&[
+            (Mana::colored(Red), 1),
+            (Mana::colored(Blue).lasting(ManaExpiry::EndOf(draw)), 1),
+            (Mana::colored(Green).lasting(ManaExpiry::Never), 1),
+            (Mana::colored(White).lasting(ManaExpiry::end_of_turn()), 1),
+        ]);
+
+        pool.empty(upkeep);
+        assert_eq!(pool.total(), 3);
+        assert_eq!(pool.count(Mana::colored(Red)), 0);
+
+        pool.empty(draw);
+        assert_eq!(pool.total(), 2);
+
+        pool.empty(Step::Ending(EndingStep::Cleanup));
+        assert_eq!(pool.total(), 1);
+
+        let mut pool = self::pool(&[(Mana::colored(Green), 2), (Mana::colored(Black), 1)]);
+        pool.empty_where(upkeep, |mana| mana.color() == Some(Green));
+        assert_eq!(pool.total(), 2);
+    }
+}
@@ -1,11 +1,11 @@
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BeginningStep {
     Untap,
     Upkeep,
     Draw,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CombatStep {
     BeginCombat,
     DeclareAttackers,
@@ -15,13 +15,13 @@ pub enum CombatStep {
     EndCombat,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EndingStep {
     End,
     Cleanup,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Step {
     Beginning(BeginningStep),
     FirstMain,
@@ -30,7 +30,7 @@ pub enum Step {
     Ending(EndingStep),
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Phase {
     Beginning,
     FirstMain,
@@ -71,9 +71,9 @@ impl Step {
     }
 
     pub fn has_priority(self) -> bool {
-        match self {
-            Step::Beginning(BeginningStep::Untap) | Step::Ending(EndingStep::Cleanup) => false,
-            _ => true,
-        }
+        !matches!(
+            self,
+            Step::Beginning(BeginningStep::Untap) | Step::Ending(EndingStep::Cleanup)
+        )
     }
 }
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::macros::{impl_deserialize_with_fromstr, impl_serialize_with_tostring};
+use crate::mana::ManaSymbol;
+use crate::ColorIdentity;
+
+/// Any symbol that can appear on a card (107).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CardSymbol {
+    /// A symbol that can appear in a mana cost.
+    Mana(ManaSymbol),
+    /// `{Y}` or `{Z}`. `{X}` is `ManaSymbol::Variable`.
+    Variable(char),
+    /// `{∞}`.
+    Infinity,
+    /// `{C/P}`.
+    ColorlessPhyrexian,
+    /// `{T}` (107.5).
+    Tap,
+    /// `{Q}` (107.6).
+    Untap,
+    /// `{E}` (107.14).
+    Energy,
+    /// `{CHAOS}` (107.12).
+    Chaos,
+    /// `{PW}` (107.11).
+    Planeswalker,
+    /// `{TK}`.
+    Ticket,
+    /// `{A}`.
+    Acorn,
+}
+
+impl CardSymbol {
+    /// The mana symbol this is, if it can appear in a `ManaCost`.
+    pub fn as_mana(self) -> Option<ManaSymbol> {
+        match self {
+            CardSymbol::Mana(symbol) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// Whether this symbol represents mana, including the ones a
+    /// `ManaCost` can't hold.
+    pub fn represents_mana(self) -> bool {
+        matches!(
+            self,
+            CardSymbol::Mana(_)
+                | CardSymbol::Variable(_)
+                | CardSymbol::Infinity
+                | CardSymbol::ColorlessPhyrexian
+        )
+    }
+
+    /// The colors of this symbol. Only mana symbols have a color (105.2).
+    pub fn colors(self) -> ColorIdentity {
+        match self {
+            CardSymbol::Mana(symbol) => symbol.colors(),
+            _ => ColorIdentity::default(),
+        }
+    }
+}
+
+impl From<ManaSymbol> for CardSymbol {
+    fn from(symbol: ManaSymbol) -> CardSymbol {
+        CardSymbol::Mana(symbol)
+    }
+}
+
+impl fmt::Display for CardSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CardSymbol::*;
+
+        if let Mana(symbol) = self {
+            return fmt::Display::fmt(symbol, f);
+        }
+
+        write!(f, "{{")?;
+        match *self {
+            Mana(_) => unreachable!(),
+            Variable(c) => write!(f, "{}", c),
+            Infinity => write!(f, "\u{221e}"),
+            ColorlessPhyrexian => write!(f, "C/P"),
+            Tap => write!(f, "T"),
+            Untap => write!(f, "Q"),
+            Energy => write!(f, "E"),
+            Chaos => write!(f, "CHAOS"),
+            Planeswalker => write!(f, "PW"),
+            Ticket => write!(f, "TK"),
+            Acorn => write!(f, "A"),
+        }?;
+        write!(f, "}}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardSymbolError {
+    symbol: String,
+}
+
+impl fmt::Display for ParseCardSymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown card symbol `{}`", self.symbol)
+    }
+}
+
+impl std::error::Error for ParseCardSymbolError {}
+
+impl CardSymbol {
+    fn from_inner(inner: &str) -> Option<CardSymbol> {
+        use self::CardSymbol::*;
+
+        if let Ok(symbol) = format!("{{{}}}", inner).parse::<ManaSymbol>() {
+            return Some(Mana(symbol));
+        }
+
+        let symbol = match inner {
+            "Y" => Variable('Y'),
+            "Z" => Variable('Z'),
+            "\u{221e}" => Infinity,
+            "C/P" => ColorlessPhyrexian,
+            "T" => Tap,
+            "Q" => Untap,
+            "E" => Energy,
+            "CHAOS" => Chaos,
+            "PW" => Planeswalker,
+            "TK" => Ticket,
+            "A" => Acorn,
+            _ => return None,
+        };
+
+        Some(symbol)
+    }
+}
+
+impl FromStr for CardSymbol {
+    type Err = ParseCardSymbolError;
+
+    fn from_str(s: &str) -> Result<CardSymbol, ParseCardSymbolError> {
+        let error = || ParseCardSymbolError {
+            symbol: s.to_string(),
+        };
+
+        if !s.starts_with('{') || !s.ends_with('}') || s.len() < 2 {
+            return Err(error());
+        }
+
+        let inner = &s[1..s.len() - 1];
+        if inner.contains(['{', '}']) {
+            return Err(error());
+        }
+
+        CardSymbol::from_inner(inner).ok_or_else(error)
+    }
+}
+
+impl_deserialize_with_fromstr!(CardSymbol);
+impl_serialize_with_tostring!(CardSymbol);
+
+/// A piece of rules text: either plain text or a single symbol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Symbol(CardSymbol),
+}
+
+/// Splits rules text into plain text and symbols. Braces that don't
+/// contain a known symbol are left in the text.
+pub fn tokenize(text: &str) -> Tokens<'_> {
+    Tokens { rest: text }
+}
+
+/// Iterator returned by `tokenize`.
+#[derive(Clone, Debug)]
+pub struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    /// The symbol at the start of `s`, if any, and its length in bytes.
+    fn symbol_at(s: &str) -> Option<(CardSymbol, usize)> {
+        if !s.starts_with('{') {
+            return None;
+        }
+
+        let end = s.find('}')?;
+        s[..=end].parse().ok().map(|symbol| (symbol, end + 1))
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Segment<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        if let Some((symbol, len)) = Tokens::symbol_at(self.rest) {
+            self.rest = &self.rest[len..];
+            return Some(Segment::Symbol(symbol));
+        }
+
+        let mut end = self.rest.len();
+        for (i, _) in self.rest.match_indices('{').filter(|&(i, _)| i > 0) {
+            if Tokens::symbol_at(&self.rest[i..]).is_some() {
+                end = i;
+                break;
+            }
+        }
+
+        let text = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        Some(Segment::Text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color::*;
+
+    #[test]
+    fn card_symbol_round_trip() {
+        #[rustfmt::skip]
+        let pairs = [
+            ("{T}", CardSymbol::Tap),
+            ("{Q}", CardSymbol::Untap),
+            ("{E}", CardSymbol::Energy),
+            ("{CHAOS}", CardSymbol::Chaos),
+            ("{PW}", CardSymbol::Planeswalker),
+            ("{\u{bd}}", CardSymbol::Mana(ManaSymbol::Half(None))),
+            ("{HR}", CardSymbol::Mana(ManaSymbol::Half(Some(Red)))),
+            ("{\u{221e}}", CardSymbol::Infinity),
+            ("{Y}", CardSymbol::Variable('Y')),
+            ("{W/U/P}", CardSymbol::Mana(ManaSymbol::HybridPhyrexian(White, Blue))),
+            ("{C/P}", CardSymbol::ColorlessPhyrexian),
+            ("{1000000}", CardSymbol::Mana(ManaSymbol::Generic(1_000_000))),
+            ("{X}", CardSymbol::Mana(ManaSymbol::Variable)),
+            ("{2/W}", CardSymbol::Mana(ManaSymbol::MonoHybrid(White))),
+            ("{G/P}", CardSymbol::Mana(ManaSymbol::Phyrexian(Green))),
+        ];
+
+        for (string, symbol) in pairs.iter() {
+            assert_eq!(string.parse::<CardSymbol>().unwrap(), *symbol);
+            assert_eq!(symbol.to_string(), *string);
+        }
+
+        assert!("{U/W/P}".parse::<CardSymbol>().is_ok());
+        assert!("{W/W/P}".parse::<CardSymbol>().is_err());
+        assert!("{T}{Q}".parse::<CardSymbol>().is_err());
+        assert!("T".parse::<CardSymbol>().is_err());
+    }
+
+    #[test]
+    fn tokenize_oracle_text() {
+        let text = "{T}, Pay {E}{E}: Add {G/U}. {Activate only once.}";
+        let segments: Vec<_> = tokenize(text).collect();
+
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Symbol(CardSymbol::Tap),
+                Segment::Text(", Pay "),
+                Segment::Symbol(CardSymbol::Energy),
+                Segment::Symbol(CardSymbol::Energy),
+                Segment::Text(": Add "),
+                Segment::Symbol(CardSymbol::Mana(ManaSymbol::Hybrid(Green, Blue))),
+                Segment::Text(". {Activate only once.}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_plain_text() {
+        assert_eq!(tokenize("").count(), 0);
+        assert_eq!(
+            tokenize("Flying").collect::<Vec<_>>(),
+            vec![Segment::Text("Flying")]
+        );
+        assert_eq!(
+            tokenize("{oops {W}").collect::<Vec<_>>(),
+            vec![
+                Segment::Text("{oops "),
+                Segment::Symbol(CardSymbol::Mana(ManaSymbol::Colored(White))),
+            ]
+        );
+    }
+}
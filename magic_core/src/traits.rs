@@ -1,22 +1,98 @@
 use std::borrow::Cow;
 
+/// The separator between the names of a split card's halves.
+pub const NAME_SEPARATOR: &str = " // ";
+
 pub trait Named {
     /// The name of this object, if it has a single name.
-    fn name(&self) -> Option<Cow<str>>;
+    fn name(&self) -> Option<Cow<'_, str>>;
 
     /// The names of this object. Default implementation can be used
-    /// if the object has a single name.
-    fn names(&self) -> Vec<Cow<str>> {
-        let name = self.name();
-        if let Some(name) = name {
-            vec![name]
-        } else {
-            unimplemented!()
+    /// if the object has at most one name.
+    fn names(&self) -> Vec<Cow<'_, str>> {
+        self.name().into_iter().collect()
+    }
+
+    /// The name to show for this object. An object with several names,
+    /// such as a split card (709.4), shows them joined with ` // `.
+    fn display_name(&self) -> Cow<'_, str> {
+        match self.name() {
+            Some(name) => name,
+            None => Cow::Owned(self.names().join(NAME_SEPARATOR)),
         }
     }
 
-    /// Tests if the names of this object match the provided name.
+    /// Tests if the names of this object match the provided name, ignoring
+    /// case, accents and punctuation. The display name of an object with
+    /// several names also matches.
     fn match_name(&self, name: &str) -> bool {
-        self.names().iter().any(|n| n == name)
+        let name = normalize_name(name);
+        self.names().iter().any(|n| normalize_name(n) == name)
+            || normalize_name(&self.display_name()) == name
+    }
+}
+
+/// Folds a name for comparison: lowercase, without accents or apostrophes,
+/// and with other punctuation (including the `//` of split cards) treated
+/// as a space. `"Lim-Dûl's Vault"` becomes `"lim duls vault"`.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut space = false;
+
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c == '\'' || c == '\u{2019}' {
+            continue;
+        }
+
+        if !c.is_alphanumeric() {
+            space = !normalized.is_empty();
+            continue;
+        }
+
+        if space {
+            normalized.push(' ');
+            space = false;
+        }
+        match fold_accent(c) {
+            Some(folded) => normalized.push_str(folded),
+            None => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+/// The unaccented form of a lowercase letter used in card names.
+fn fold_accent(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è' | 'é' | 'ê' | 'ë' => "e",
+        'ì' | 'í' | 'î' | 'ï' => "i",
+        'ñ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'œ' => "oe",
+        'ù' | 'ú' | 'û' | 'ü' => "u",
+        'ý' | 'ÿ' => "y",
+        'ß' => "ss",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_names() {
+        assert_eq!(normalize_name("Lim-Dûl's Vault"), "lim duls vault");
+        assert_eq!(normalize_name("Jötun Grunt"), "jotun grunt");
+        assert_eq!(normalize_name("Æther Vial"), "aether vial");
+        assert_eq!(normalize_name("Fire // Ice"), "fire ice");
+        assert_eq!(normalize_name("fire/ice"), "fire ice");
+        assert_eq!(normalize_name("Urza’s Saga"), "urzas saga");
+        assert_eq!(normalize_name("  Ach! Hans, Run!  "), "ach hans run");
     }
 }
@@ -1,58 +1,32 @@
-use serde::{Deserialize, Serialize};
-
-use crate::macros::{impl_display_with_serialize, impl_fromstr_with_deserialize};
-
-/// Types (205.2a)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Type {
-    Artifact,
-    Conspiracy,
-    Creature,
-    Enchantment,
-    Instant,
-    Land,
-    Phenomenon,
-    Plane,
-    Planeswalker,
-    Scheme,
-    Sorcery,
-    Tribal,
-    Vanguard,
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::macros::{
+    impl_display_with_serialize, impl_fromstr_with_deserialize, impl_serialize_with_tostring,
+};
+
+/// An error returned when parsing a type name that isn't known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTypeError {
+    name: String,
 }
 
-impl_display_with_serialize!(Type);
-impl_fromstr_with_deserialize!(Type);
-
-/// Subtypes (205.3)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum Subtype {
-    Artifact(ArtifactType),
-    Enchantment(EnchantmentType),
-    Land(LandType),
-    Planeswalker(PlaneswalkerType),
-    Spell(SpellType),
-    Creature(CreatureType),
-    Plane(PlanarType),
+impl ParseTypeError {
+    /// The name that failed to parse.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
-impl_display_with_serialize!(Subtype);
-impl_fromstr_with_deserialize!(Subtype);
-
-impl Subtype {
-    pub fn valid_for(self, type_: Type) -> bool {
-        match self {
-            Subtype::Artifact(_) => type_ == Type::Artifact,
-            Subtype::Enchantment(_) => type_ == Type::Enchantment,
-            Subtype::Land(_) => type_ == Type::Land,
-            Subtype::Planeswalker(_) => type_ == Type::Planeswalker,
-            Subtype::Spell(_) => type_ == Type::Instant || type_ == Type::Sorcery,
-            Subtype::Creature(_) => type_ == Type::Creature || type_ == Type::Tribal,
-            Subtype::Plane(_) => type_ == Type::Plane,
-        }
+impl fmt::Display for ParseTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown type `{}`", self.name)
     }
 }
 
+impl std::error::Error for ParseTypeError {}
+
 pub trait IntoSubtype {
     fn into_subtype(self) -> Subtype;
 }
@@ -67,451 +41,254 @@ macro_rules! impl_into_subtype {
     };
 }
 
-/// Artifact subtypes (205.3g)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum ArtifactType {
-    Clue,
-    Contraption,
-    Equipment,
-    Fortification,
-    Treasure,
-    Vehicle,
+macro_rules! type_name {
+    ($variant:ident) => {
+        stringify!($variant)
+    };
+    ($variant:ident, $name:literal) => {
+        $name
+    };
 }
 
-impl_display_with_serialize!(ArtifactType);
-impl_fromstr_with_deserialize!(ArtifactType);
+/// Defines the enum for one family of subtypes. Each family has an `Other`
+/// variant so that subtypes printed after this crate was written can still
+/// be represented. `FromStr` only accepts known names, while deserializing
+/// falls back to `Other`.
+macro_rules! subtype_family {
+    (
+        $(#[$meta:meta])*
+        $ty:ident, $subtype:ident {
+            $($variant:ident $(=> $name:literal)?,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $ty {
+            $($variant,)*
+            /// A subtype this crate doesn't know about.
+            Other(String),
+        }
 
-impl_into_subtype!(ArtifactType, Artifact);
+        impl $ty {
+            /// The names of every known subtype in this family.
+            pub const NAMES: &'static [&'static str] = &[$(type_name!($variant $(, $name)?),)*];
 
-/// Enchantment subtypes (205.3h)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum EnchantmentType {
-    Aura,
-    Cartouche,
-    Curse,
-    Saga,
-    Shrine,
-}
+            /// The known subtype with this name.
+            pub fn known(name: &str) -> Option<$ty> {
+                match name {
+                    $(type_name!($variant $(, $name)?) => Some($ty::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// The subtype with this name, or `Other` if it isn't known.
+            pub fn from_name(name: &str) -> $ty {
+                $ty::known(name).unwrap_or_else(|| $ty::Other(name.to_string()))
+            }
+
+            /// The printed name of this subtype.
+            pub fn name(&self) -> &str {
+                match self {
+                    $($ty::$variant => type_name!($variant $(, $name)?),)*
+                    $ty::Other(name) => name,
+                }
+            }
+
+            pub fn is_known(&self) -> bool {
+                !matches!(self, $ty::Other(_))
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = ParseTypeError;
+
+            fn from_str(s: &str) -> Result<$ty, ParseTypeError> {
+                $ty::known(s).ok_or_else(|| ParseTypeError {
+                    name: s.to_string(),
+                })
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.name())
+            }
+        }
 
-impl_display_with_serialize!(EnchantmentType);
-impl_fromstr_with_deserialize!(EnchantmentType);
-
-impl_into_subtype!(EnchantmentType, Enchantment);
-
-/// Land subtypes (205.3i)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum LandType {
-    Desert,
-    Forest,
-    Gate,
-    Island,
-    Lair,
-    Locus,
-    Mine,
-    Mountain,
-    Plains,
-    #[serde(rename = "Power-Plant")]
-    PowerPlant,
-    Swamp,
-    Tower,
-    #[serde(rename = "Urza's")]
-    Urzas,
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                let name = String::deserialize(deserializer)?;
+                Ok($ty::from_name(&name))
+            }
+        }
+
+        impl_into_subtype!($ty, $subtype);
+    };
 }
 
-impl_display_with_serialize!(LandType);
-impl_fromstr_with_deserialize!(LandType);
-
-impl_into_subtype!(LandType, Land);
-
-/// Planeswalker subtypes (205.3j)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum PlaneswalkerType {
-    Ajani,
-    Aminatou,
-    Angrath,
-    Arlinn,
-    Ashiok,
-    Bolas,
-    Chandra,
-    Dack,
-    Daretti,
-    Domri,
-    Dovin,
-    Elspeth,
-    Estrid,
-    Freyalise,
-    Garruk,
-    Gideon,
-    Huatli,
-    Jace,
-    Jaya,
-    Karn,
-    Kaya,
-    Kiora,
-    Koth,
-    Liliana,
-    Nahiri,
-    Narset,
-    Nissa,
-    Nixilis,
-    Ral,
-    Rowan,
-    Saheeli,
-    Samut,
-    Sarkhan,
-    Sorin,
-    Tamiyo,
-    Teferi,
-    Tezzeret,
-    Tibalt,
-    Ugin,
-    Venser,
-    Vivien,
-    Vraska,
-    Will,
-    Windgrace,
-    Xenagos,
-    Yanggu,
-    Yanling,
+/// Defines a card type or supertype enum. These are closed: new card types
+/// are rare enough that they are added to the catalog instead.
+macro_rules! closed_type_enum {
+    ($(#[$meta:meta])* $ty:ident { $($variant:ident,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum $ty {
+            $($variant,)*
+        }
+
+        impl $ty {
+            /// Every value of this enum.
+            pub const ALL: &'static [$ty] = &[$($ty::$variant,)*];
+
+            /// The names of every value of this enum.
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($variant),)*];
+        }
+
+        impl_display_with_serialize!($ty);
+        impl_fromstr_with_deserialize!($ty);
+    };
 }
 
-impl_display_with_serialize!(PlaneswalkerType);
-impl_fromstr_with_deserialize!(PlaneswalkerType);
+/// Defines `Subtype` with one variant per subtype family, each valid for
+/// the listed card types.
+macro_rules! subtypes {
+    ($($variant:ident($family:ident) => [$($type_:ident),*],)*) => {
+        /// Subtypes (205.3)
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum Subtype {
+            $($variant($family),)*
+        }
+
+        impl Subtype {
+            pub fn valid_for(&self, type_: Type) -> bool {
+                match self {
+                    $(Subtype::$variant(_) => matches!(type_, $(Type::$type_)|*),)*
+                }
+            }
 
-impl_into_subtype!(PlaneswalkerType, Planeswalker);
+            /// The printed name of this subtype.
+            pub fn name(&self) -> &str {
+                match self {
+                    $(Subtype::$variant(subtype) => subtype.name(),)*
+                }
+            }
 
-/// Instant and sorcery subtypes (205.3k)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum SpellType {
-    Arcane,
-    Trap,
+            /// Whether this is one of the subtypes this crate knows by
+            /// name, rather than an `Other` fallback.
+            pub fn is_known(&self) -> bool {
+                match self {
+                    $(Subtype::$variant(subtype) => subtype.is_known(),)*
+                }
+            }
+
+            /// The known subtype with this name, preferring ones that are
+            /// valid for one of `types`.
+            pub fn known(name: &str, types: &[Type]) -> Option<Subtype> {
+                let candidates = vec![$($family::known(name).map(Subtype::$variant),)*];
+                let mut candidates = candidates.into_iter().flatten().peekable();
+                let first = candidates.peek().cloned();
+
+                candidates
+                    .find(|subtype| types.iter().any(|&ty| subtype.valid_for(ty)))
+                    .or(first)
+            }
+
+            /// The names of the known subtypes of `type_`.
+            pub fn names_for(type_: Type) -> &'static [&'static str] {
+                $(
+                    if matches!(type_, $(Type::$type_)|*) {
+                        return $family::NAMES;
+                    }
+                )*
+                &[]
+            }
+
+            /// An `Other` subtype named `name` in the family for `type_`, if
+            /// that type has subtypes.
+            fn other(name: &str, type_: Type) -> Option<Subtype> {
+                $(
+                    if matches!(type_, $(Type::$type_)|*) {
+                        return Some(Subtype::$variant($family::Other(name.to_string())));
+                    }
+                )*
+                None
+            }
+        }
+    };
 }
 
-impl_display_with_serialize!(SpellType);
-impl_fromstr_with_deserialize!(SpellType);
-
-impl_into_subtype!(SpellType, Spell);
-
-/// Creature and tribal subtypes (205.3m)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum CreatureType {
-    Advisor,
-    Aetherborn,
-    Ally,
-    Angel,
-    Antelope,
-    Ape,
-    Archer,
-    Archon,
-    Artificer,
-    Assassin,
-    #[serde(rename = "Assembly-Worker")]
-    AssemblyWorker,
-    Atog,
-    Aurochs,
-    Avatar,
-    Azra,
-    Badger,
-    Barbarian,
-    Basilisk,
-    Bat,
-    Bear,
-    Beast,
-    Beeble,
-    Berserker,
-    Bird,
-    Blinkmoth,
-    Boar,
-    Bringer,
-    Brushwagg,
-    Camarid,
-    Camel,
-    Caribou,
-    Carrier,
-    Cat,
-    Centaur,
-    Cephalid,
-    Chimera,
-    Citizen,
-    Cleric,
-    Cockatrice,
-    Construct,
-    Coward,
-    Crab,
-    Crocodile,
-    Cyclops,
-    Dauthi,
-    Demon,
-    Deserter,
-    Devil,
-    Dinosaur,
-    Djinn,
-    Dragon,
-    Drake,
-    Dreadnought,
-    Drone,
-    Druid,
-    Dryad,
-    Dwarf,
-    Efreet,
-    Egg,
-    Elder,
-    Eldrazi,
-    Elemental,
-    Elephant,
-    Elf,
-    Elk,
-    Eye,
-    Faerie,
-    Ferret,
-    Fish,
-    Flagbearer,
-    Fox,
-    Frog,
-    Fungus,
-    Gargoyle,
-    Germ,
-    Giant,
-    Gnome,
-    Goat,
-    Goblin,
-    God,
-    Golem,
-    Gorgon,
-    Graveborn,
-    Gremlin,
-    Griffin,
-    Hag,
-    Harpy,
-    Hellion,
-    Hippo,
-    Hippogriff,
-    Homarid,
-    Homunculus,
-    Horror,
-    Horse,
-    Hound,
-    Human,
-    Hydra,
-    Hyena,
-    Illusion,
-    Imp,
-    Incarnation,
-    Insect,
-    Jackal,
-    Jellyfish,
-    Juggernaut,
-    Kavu,
-    Kirin,
-    Kithkin,
-    Knight,
-    Kobold,
-    Kor,
-    Kraken,
-    Lamia,
-    Lammasu,
-    Leech,
-    Leviathan,
-    Lhurgoyf,
-    Licid,
-    Lizard,
-    Manticore,
-    Masticore,
-    Mercenary,
-    Merfolk,
-    Metathran,
-    Minion,
-    Minotaur,
-    Mole,
-    Monger,
-    Mongoose,
-    Monk,
-    Monkey,
-    Moonfolk,
-    Mutant,
-    Myr,
-    Mystic,
-    Naga,
-    Nautilus,
-    Nephilim,
-    Nightmare,
-    Nightstalker,
-    Ninja,
-    Noggle,
-    Nomad,
-    Nymph,
-    Octopus,
-    Ogre,
-    Ooze,
-    Orb,
-    Orc,
-    Orgg,
-    Ouphe,
-    Ox,
-    Oyster,
-    Pangolin,
-    Pegasus,
-    Pentavite,
-    Pest,
-    Phelddagrif,
-    Phoenix,
-    Pilot,
-    Pincher,
-    Pirate,
-    Plant,
-    Praetor,
-    Prism,
-    Processor,
-    Rabbit,
-    Rat,
-    Rebel,
-    Reflection,
-    Rhino,
-    Rigger,
-    Rogue,
-    Sable,
-    Salamander,
-    Samurai,
-    Sand,
-    Saproling,
-    Satyr,
-    Scarecrow,
-    Scion,
-    Scorpion,
-    Scout,
-    Serf,
-    Serpent,
-    Servo,
-    Shade,
-    Shaman,
-    Shapeshifter,
-    Sheep,
-    Siren,
-    Skeleton,
-    Slith,
-    Sliver,
-    Slug,
-    Snake,
-    Soldier,
-    Soltari,
-    Spawn,
-    Specter,
-    Spellshaper,
-    Sphinx,
-    Spider,
-    Spike,
-    Spirit,
-    Splinter,
-    Sponge,
-    Squid,
-    Squirrel,
-    Starfish,
-    Surrakar,
-    Survivor,
-    Tetravite,
-    Thalakos,
-    Thopter,
-    Thrull,
-    Treefolk,
-    Trilobite,
-    Triskelavite,
-    Troll,
-    Turtle,
-    Unicorn,
-    Vampire,
-    Vedalken,
-    Viashino,
-    Volver,
-    Wall,
-    Warrior,
-    Weird,
-    Werewolf,
-    Whale,
-    Wizard,
-    Wolf,
-    Wolverine,
-    Wombat,
-    Worm,
-    Wraith,
-    Wurm,
-    Yeti,
-    Zombie,
-    Zubera,
+// The enums in this module are generated by `build.rs` from the type
+// catalog in `data/CardTypes.json`. To add a type, edit the catalog.
+include!(concat!(env!("OUT_DIR"), "/types.rs"));
+
+/// Card types in the order their subtype family is tried by
+/// `Subtype::from_name`. Creature types are by far the most common, so
+/// they come first and are also the fallback.
+const FAMILY_PRIORITY: &[Type] = &[
+    Type::Creature,
+    Type::Kindred,
+    Type::Tribal,
+    Type::Planeswalker,
+    Type::Land,
+    Type::Artifact,
+    Type::Enchantment,
+    Type::Battle,
+    Type::Instant,
+    Type::Sorcery,
+    Type::Plane,
+];
+
+impl Subtype {
+    /// The subtype with this name on an object with the given types. Names
+    /// this crate doesn't know are assumed to belong to the family that
+    /// goes with `types`, or to be creature types if none does.
+    pub fn from_name(name: &str, types: &[Type]) -> Subtype {
+        if let Some(subtype) = Subtype::known(name, types) {
+            return subtype;
+        }
+
+        FAMILY_PRIORITY
+            .iter()
+            .filter(|type_| types.contains(type_))
+            .find_map(|&type_| Subtype::other(name, type_))
+            .unwrap_or_else(|| Subtype::Creature(CreatureType::Other(name.to_string())))
+    }
 }
 
-impl_display_with_serialize!(CreatureType);
-impl_fromstr_with_deserialize!(CreatureType);
-
-impl_into_subtype!(CreatureType, Creature);
-
-/// Plane types (205.3n)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum PlanarType {
-    Alara,
-    Arkhos,
-    Azgol,
-    Belenon,
-    #[serde(rename = "Bolas's Meditation Realm")]
-    BolassMeditationRealm,
-    Dominaria,
-    Equilor,
-    Ergamon,
-    Fabacin,
-    Innistrad,
-    Iquatana,
-    Ir,
-    Kaldheim,
-    Kamigawa,
-    Karsus,
-    Kephalai,
-    Kinshala,
-    Kolbahan,
-    Kyneth,
-    Lorwyn,
-    Luvion,
-    Mercadia,
-    Mirrodin,
-    Moag,
-    Mongseng,
-    Muraganda,
-    #[serde(rename = "New Phyrexia")]
-    NewPhyrexia,
-    Phyrexia,
-    Pyrulea,
-    Rabiah,
-    Rath,
-    Ravnica,
-    Regatha,
-    Segovia,
-    #[serde(rename = "Serra's Realm")]
-    SerrasRealm,
-    Shadowmoor,
-    Shandalar,
-    Ulgrotha,
-    Valla,
-    Vryn,
-    Wildfire,
-    Xerex,
-    Zendikar,
+impl fmt::Display for Subtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
-impl_display_with_serialize!(PlanarType);
-impl_fromstr_with_deserialize!(PlanarType);
+/// Parses a known subtype. Use `Subtype::from_name` to accept subtypes this
+/// crate doesn't know about.
+impl FromStr for Subtype {
+    type Err = ParseTypeError;
 
-impl_into_subtype!(PlanarType, Plane);
+    fn from_str(s: &str) -> Result<Subtype, ParseTypeError> {
+        Subtype::known(s, &[]).ok_or_else(|| ParseTypeError {
+            name: s.to_string(),
+        })
+    }
+}
 
-/// Supertypes (205.4c)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Supertype {
-    Basic,
-    Legendary,
-    Ongoing,
-    Snow,
-    World,
+impl<'de> Deserialize<'de> for Subtype {
+    /// Deserializes any name, like the subtype families. Names this crate
+    /// doesn't know become creature types, as in `Subtype::from_name`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Subtype, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Subtype::from_name(&name, &[]))
+    }
 }
 
-impl_display_with_serialize!(Supertype);
-impl_fromstr_with_deserialize!(Supertype);
+impl_serialize_with_tostring!(Subtype);
 
 #[cfg(test)]
 mod tests {
@@ -579,6 +356,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_subtypes() {
+        assert!("Wizzard".parse::<Subtype>().is_err());
+        assert!("Wizzard".parse::<CreatureType>().is_err());
+
+        let wizzard: CreatureType = serde_json::from_str("\"Wizzard\"").unwrap();
+        assert_eq!(wizzard, CreatureType::Other("Wizzard".to_string()));
+        assert_eq!(serde_json::to_string(&wizzard).unwrap(), "\"Wizzard\"");
+        assert!(!wizzard.is_known());
+
+        let zzyzx: Subtype = serde_json::from_str("\"Zzyzx\"").unwrap();
+        assert_eq!(
+            zzyzx,
+            Subtype::Creature(CreatureType::Other("Zzyzx".to_string()))
+        );
+        assert_eq!(serde_json::to_string(&zzyzx).unwrap(), "\"Zzyzx\"");
+        assert_eq!(
+            serde_json::from_str::<Subtype>(&serde_json::to_string(&zzyzx).unwrap()).unwrap(),
+            zzyzx
+        );
+
+        assert_eq!(
+            Subtype::from_name("Grove", &[Type::Land]),
+            Subtype::Land(LandType::Other("Grove".to_string()))
+        );
+        assert_eq!(
+            Subtype::from_name("Forest", &[Type::Creature]),
+            Subtype::Land(LandType::Forest)
+        );
+    }
+
+    #[test]
+    fn generated_types_match_catalog() {
+        use serde_json::Value;
+        use std::collections::BTreeSet;
+
+        let catalog: Value = serde_json::from_str(include_str!("../data/CardTypes.json")).unwrap();
+        let data = catalog["data"].as_object().unwrap();
+
+        let names = |key: &str, field: &str| -> BTreeSet<String> {
+            data[key][field]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|name| name.as_str().unwrap().to_string())
+                .collect()
+        };
+        let set = |names: &[&str]| -> BTreeSet<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        };
+
+        assert_eq!(Type::NAMES.len(), data.len());
+        for (key, type_) in data.keys().zip(Type::NAMES) {
+            assert_eq!(key, &type_.to_lowercase());
+            assert_eq!(type_.parse::<Type>().unwrap().to_string(), *type_);
+        }
+
+        assert_eq!(set(Supertype::NAMES), names("artifact", "superTypes"));
+
+        assert_eq!(set(ArtifactType::NAMES), names("artifact", "subTypes"));
+        assert_eq!(
+            set(EnchantmentType::NAMES),
+            names("enchantment", "subTypes")
+        );
+        assert_eq!(set(LandType::NAMES), names("land", "subTypes"));
+        assert_eq!(
+            set(PlaneswalkerType::NAMES),
+            names("planeswalker", "subTypes")
+        );
+        assert_eq!(set(CreatureType::NAMES), names("creature", "subTypes"));
+        assert_eq!(set(PlanarType::NAMES), names("plane", "subTypes"));
+        assert_eq!(set(BattleType::NAMES), names("battle", "subTypes"));
+
+        let spell_types = &names("instant", "subTypes") | &names("sorcery", "subTypes");
+        assert_eq!(set(SpellType::NAMES), spell_types);
+
+        for (key, type_) in data.keys().zip(Type::NAMES) {
+            let type_: Type = type_.parse().unwrap();
+
+            for name in names(key, "subTypes") {
+                let subtype = Subtype::from_name(&name, &[type_]);
+                assert!(subtype.is_known(), "{}", name);
+                assert!(subtype.valid_for(type_), "{}", name);
+                assert_eq!(subtype.to_string(), name);
+            }
+        }
+    }
+
+    #[test]
+    fn recent_catalog_entries() {
+        assert_eq!(
+            CreatureType::known("Time Lord"),
+            Some(CreatureType::TimeLord)
+        );
+        assert_eq!(CreatureType::known("C'tan"), Some(CreatureType::Ctan));
+        assert_eq!(CreatureType::TimeLord.to_string(), "Time Lord");
+        assert_eq!(
+            Subtype::known("Mount", &[Type::Creature]),
+            Some(Subtype::Creature(CreatureType::Mount))
+        );
+        assert_eq!(
+            Subtype::known("Spacecraft", &[Type::Artifact]),
+            Some(Subtype::Artifact(ArtifactType::Spacecraft))
+        );
+        assert_eq!(
+            Subtype::known("Planet", &[Type::Land]),
+            Some(Subtype::Land(LandType::Planet))
+        );
+        assert_eq!(
+            Subtype::known("Siege", &[Type::Battle]),
+            Some(Subtype::Battle(BattleType::Siege))
+        );
+    }
+
     #[test]
     fn bolas_realm_json() {
         assert_eq!(
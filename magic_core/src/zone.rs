@@ -0,0 +1,11 @@
+/// The zones of the game (400.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Zone {
+    Library,
+    Hand,
+    Battlefield,
+    Graveyard,
+    Stack,
+    Exile,
+    Command,
+}
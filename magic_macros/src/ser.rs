@@ -200,11 +200,6 @@ impl ser::Serializer for Serializer {
     ) -> Result<Impossible, Error> {
         Err(Error::impossible(ImpossibleKind::StructVariant))
     }
-
-    #[cfg(not(any(feature = "std", feature = "alloc")))]
-    fn collect_str<T: ?Sized + ::core::fmt::Display>(self, _value: &T) -> Result<String, Error> {
-        unimplemented!()
-    }
 }
 
 pub fn to_string<T: ser::Serialize>(value: &T) -> Result<String, Error> {
@@ -0,0 +1,765 @@
+//! Conversion of MTGJSON cards into `magic_core::card::Card`.
+
+use crate::Card;
+use magic_core::card::{
+    self, CardData, CardDataError, LoyaltyValue, ParseMode, ParseValueError, PtValue,
+    SpecialLayout, TypeLine, TypeLineReadError,
+};
+use magic_core::mana::{ManaCost, ParseManaCostError};
+use magic_core::type_::{Subtype, Supertype, Type};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Why a field of an MTGJSON card couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    /// A field the layout needs is absent.
+    Missing,
+    UnknownLayout(String),
+    /// A name in `supertypes` or `types` that isn't a known type.
+    UnknownType(String),
+    /// The prototype cost in the rules text of a prototype card.
+    ManaCost(ParseManaCostError),
+    TypeLine(TypeLineReadError),
+    Value(ParseValueError),
+    /// The type line built from `supertypes`, `types` and `subtypes`
+    /// doesn't match the one parsed from `type`.
+    TypeLineMismatch {
+        arrays: Box<TypeLine>,
+        printed: Box<TypeLine>,
+    },
+    /// The fields of a face parsed but don't make a valid card.
+    Face(CardDataError),
+    /// The card has a different number of records than it has `names`.
+    FaceCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for FieldErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldErrorKind::Missing => write!(f, "missing"),
+            FieldErrorKind::UnknownLayout(layout) => write!(f, "unknown layout `{}`", layout),
+            FieldErrorKind::UnknownType(name) => write!(f, "unknown type `{}`", name),
+            FieldErrorKind::ManaCost(error) => write!(f, "{}", error),
+            FieldErrorKind::TypeLine(error) => write!(f, "{}", error),
+            FieldErrorKind::Value(error) => write!(f, "{}", error),
+            FieldErrorKind::TypeLineMismatch { arrays, printed } => write!(
+                f,
+                "the type arrays give `{}` but the type line is `{}`",
+                arrays, printed
+            ),
+            FieldErrorKind::Face(error) => write!(f, "{}", error),
+            FieldErrorKind::FaceCount { expected, found } => {
+                write!(f, "expected {} faces, found {}", expected, found)
+            }
+        }
+    }
+}
+
+/// A field of an MTGJSON card that couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    face: String,
+    field: &'static str,
+    kind: FieldErrorKind,
+}
+
+impl FieldError {
+    /// The name of the face with the error.
+    pub fn face(&self) -> &str {
+        &self.face
+    }
+
+    /// The name of the field in MTGJSON's card object, such as `"type"`.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    pub fn kind(&self) -> &FieldErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.face, self.field, self.kind)
+    }
+}
+
+/// Every field of an MTGJSON card that couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    names: Vec<String>,
+    errors: Vec<FieldError>,
+}
+
+impl ConversionError {
+    /// The names of the card's faces.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "couldn't convert `{}`: ", self.names.join(" // "))?;
+        for (i, error) in self.errors.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { "; " } else { "" }, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// Collects the errors of one card while its faces are converted.
+struct Converter {
+    errors: Vec<FieldError>,
+}
+
+impl Converter {
+    fn error(&mut self, record: &Card, field: &'static str, kind: FieldErrorKind) {
+        self.errors.push(FieldError {
+            face: record.name.clone(),
+            field,
+            kind,
+        });
+    }
+
+    /// Builds the type line from the typed arrays and checks it against
+    /// the printed one.
+    fn type_line(&mut self, record: &Card) -> Option<TypeLine> {
+        let errors = self.errors.len();
+        let mut type_line = TypeLine::new();
+
+        for name in &record.supertypes {
+            match name.parse::<Supertype>() {
+                Ok(supertype) => type_line.add_supertype(supertype),
+                Err(_) => {
+                    let kind = FieldErrorKind::UnknownType(name.clone());
+                    self.error(record, "supertypes", kind);
+                }
+            }
+        }
+
+        for name in &record.types {
+            match name.parse::<Type>() {
+                Ok(type_) => type_line.add_type(type_),
+                Err(_) => self.error(record, "types", FieldErrorKind::UnknownType(name.clone())),
+            }
+        }
+
+        let types: Vec<Type> = type_line.types_iter().collect();
+        for name in &record.subtypes {
+            type_line.add_subtype(Subtype::from_name(name, &types));
+        }
+
+        if self.errors.len() > errors {
+            return None;
+        }
+
+        let printed = match TypeLine::parse(&record.type_, ParseMode::Lenient) {
+            Ok(printed) => printed,
+            Err(error) => {
+                self.error(record, "type", FieldErrorKind::TypeLine(error));
+                return None;
+            }
+        };
+        if printed != type_line {
+            let kind = FieldErrorKind::TypeLineMismatch {
+                arrays: Box::new(type_line),
+                printed: Box::new(printed),
+            };
+            self.error(record, "type", kind);
+            return None;
+        }
+
+        Some(type_line)
+    }
+
+    fn value<T>(&mut self, record: &Card, field: &'static str, value: &str) -> Option<T>
+    where
+        T: std::str::FromStr<Err = ParseValueError>,
+    {
+        match value.parse() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.error(record, field, FieldErrorKind::Value(error));
+                None
+            }
+        }
+    }
+
+    /// Converts the record of one face, reporting every field that fails.
+    fn face(&mut self, record: &Card) -> Option<CardData> {
+        let errors = self.errors.len();
+
+        let type_line = self.type_line(record);
+
+        let power_toughness = match (&record.power, &record.toughness) {
+            (Some(power), Some(toughness)) => {
+                let power: Option<PtValue> = self.value(record, "power", power);
+                let toughness: Option<PtValue> = self.value(record, "toughness", toughness);
+                power.zip(toughness)
+            }
+            (Some(_), None) => {
+                self.error(record, "toughness", FieldErrorKind::Missing);
+                None
+            }
+            (None, Some(_)) => {
+                self.error(record, "power", FieldErrorKind::Missing);
+                None
+            }
+            (None, None) => None,
+        };
+
+        if self.errors.len() > errors {
+            return None;
+        }
+
+        let mut builder = CardData::builder(record.name.clone())
+            .mana_cost(record.mana_cost.clone())
+            .type_line(type_line?)
+            .text(record.text.clone());
+
+        // MTGJSON has no color indicator, but a colored face without a mana
+        // cost can only get its colors from one (204).
+        if record.mana_cost.is_empty() && !record.colors.is_empty() {
+            builder = builder.color_indicator(record.colors.iter().cloned().collect());
+        }
+        if let Some((power, toughness)) = power_toughness {
+            builder = builder.power_toughness(power, toughness);
+        }
+        if let Some(loyalty) = record.loyalty {
+            builder = builder.loyalty(LoyaltyValue::Number(loyalty));
+        }
+
+        match builder.build() {
+            Ok(card_data) => Some(card_data),
+            Err(error) => {
+                let field = card_data_field(&error);
+                self.error(record, field, FieldErrorKind::Face(error));
+                None
+            }
+        }
+    }
+
+    /// Converts the records of one card, ordered as in its `names`. A meld
+    /// pair gives one card for each of its two parts.
+    fn cards(&mut self, records: &[&Card]) -> Vec<card::Card> {
+        let first = records[0];
+        let layout = first.layout.as_str();
+
+        if let Some(kind) = special_layout(layout) {
+            return self
+                .face(first)
+                .map(|face| card::Card::Special { kind, face })
+                .into_iter()
+                .collect();
+        }
+
+        let expected = match layout {
+            "normal" | "mutate" => {
+                return self
+                    .face(first)
+                    .map(card::Card::Normal)
+                    .into_iter()
+                    .collect()
+            }
+            "prototype" => return self.prototype(first).into_iter().collect(),
+            "meld" => 3,
+            "split" | "aftermath" | "room" | "flip" | "double-faced" | "transform" | "battle"
+            | "modal_dfc" | "adventure" => 2,
+            _ => {
+                let kind = FieldErrorKind::UnknownLayout(layout.to_string());
+                self.error(first, "layout", kind);
+                return vec![];
+            }
+        };
+
+        if records.len() != expected {
+            let kind = FieldErrorKind::FaceCount {
+                expected,
+                found: records.len(),
+            };
+            self.error(first, "names", kind);
+            return vec![];
+        }
+
+        let faces: Vec<Option<CardData>> = records.iter().map(|record| self.face(record)).collect();
+        let faces = match faces.into_iter().collect::<Option<Vec<_>>>() {
+            Some(faces) => faces,
+            None => return vec![],
+        };
+
+        if layout == "meld" {
+            return self.meld(records, faces);
+        }
+
+        let mut faces = faces.into_iter();
+        let (first, second) = (faces.next().unwrap(), faces.next().unwrap());
+        let card = match layout {
+            "split" => {
+                let fuse = has_keyword(&first, "Fuse") || has_keyword(&second, "Fuse");
+                card::Card::Split {
+                    left: first,
+                    right: second,
+                    fuse,
+                }
+            }
+            "aftermath" => card::Card::Aftermath {
+                top: first,
+                bottom: second,
+            },
+            "room" => card::Card::Room {
+                left: first,
+                right: second,
+            },
+            "flip" => card::Card::Flip {
+                top: first,
+                bottom: second,
+            },
+            "adventure" => card::Card::Adventure {
+                creature: first,
+                adventure: second,
+            },
+            _ => card::Card::DoubleFaced {
+                front: first,
+                back: second,
+                modal: layout == "modal_dfc",
+            },
+        };
+
+        vec![card]
+    }
+
+    /// MTGJSON gives a prototype card one record, with the prototype's
+    /// cost and power and toughness in its rules text:
+    /// `Prototype {1}{B}{B} — 3/3`.
+    fn prototype(&mut self, record: &Card) -> Option<card::Card> {
+        let main = self.face(record)?;
+
+        let prototype = main.text().lines().find_map(|line| {
+            let (mana_cost, pt) = line.strip_prefix("Prototype ")?.split_once(" \u{2014} ")?;
+            let (power, toughness) = pt.split_once('/')?;
+            Some((mana_cost, power, toughness))
+        });
+        let (mana_cost, power, toughness) = match prototype {
+            Some(prototype) => prototype,
+            None => {
+                self.error(record, "text", FieldErrorKind::Missing);
+                return None;
+            }
+        };
+
+        let mana_cost = match mana_cost.parse::<ManaCost>() {
+            Ok(mana_cost) => mana_cost,
+            Err(error) => {
+                self.error(record, "text", FieldErrorKind::ManaCost(error));
+                return None;
+            }
+        };
+        let power: Option<PtValue> = self.value(record, "text", power);
+        let toughness: Option<PtValue> = self.value(record, "text", toughness);
+        let (power, toughness) = power.zip(toughness)?;
+
+        let prototype = CardData::builder(record.name.clone())
+            .mana_cost(mana_cost)
+            .type_line(main.type_line().clone())
+            .text(record.text.clone())
+            .power_toughness(power, toughness)
+            .build();
+        match prototype {
+            Ok(prototype) => Some(card::Card::Prototype {
+                card: main,
+                prototype,
+            }),
+            Err(error) => {
+                let field = card_data_field(&error);
+                self.error(record, field, FieldErrorKind::Face(error));
+                None
+            }
+        }
+    }
+
+    /// MTGJSON lists the three cards of a meld set together. The meld result
+    /// is the one without a mana cost.
+    fn meld(&mut self, records: &[&Card], faces: Vec<CardData>) -> Vec<card::Card> {
+        let result = match records
+            .iter()
+            .position(|record| record.mana_cost.is_empty())
+        {
+            Some(result) => result,
+            None => {
+                self.error(records[0], "names", FieldErrorKind::Missing);
+                return vec![];
+            }
+        };
+        let parts: Vec<usize> = (0..records.len()).filter(|&i| i != result).collect();
+
+        parts
+            .iter()
+            .map(|&part| {
+                let partner = parts.iter().find(|&&other| other != part).unwrap();
+                card::Card::Meld {
+                    front: faces[part].clone(),
+                    melded: faces[result].clone(),
+                    partner: records[*partner].name.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The special layout with this MTGJSON name.
+fn special_layout(layout: &str) -> Option<SpecialLayout> {
+    let layout = match layout {
+        "leveler" => SpecialLayout::Leveler,
+        "saga" => SpecialLayout::Saga,
+        "class" => SpecialLayout::Class,
+        "case" => SpecialLayout::Case,
+        "plane" | "phenomenon" | "planar" => SpecialLayout::Planar,
+        "scheme" => SpecialLayout::Scheme,
+        "vanguard" => SpecialLayout::Vanguard,
+        "token" => SpecialLayout::Token,
+        "emblem" => SpecialLayout::Emblem,
+        "augment" => SpecialLayout::Augment,
+        "host" => SpecialLayout::Host,
+        _ => return None,
+    };
+    Some(layout)
+}
+
+/// The MTGJSON field a `CardDataError` is about.
+fn card_data_field(error: &CardDataError) -> &'static str {
+    match error {
+        CardDataError::EmptyName => "name",
+        CardDataError::InvalidTypeLine(_) => "type",
+        CardDataError::UnexpectedPowerToughness => "power",
+        CardDataError::IncompletePowerToughness => "toughness",
+        CardDataError::UnexpectedLoyalty => "loyalty",
+    }
+}
+
+fn has_keyword(face: &CardData, keyword: &str) -> bool {
+    face.text().lines().any(|line| line.starts_with(keyword))
+}
+
+/// The key that groups the records of one card: its `names`, and for
+/// cards other than meld cards its collector number without the face
+/// letter, so that reprints within a set stay apart.
+fn group_key(record: &Card) -> (Vec<String>, Option<String>) {
+    let names = record
+        .names
+        .clone()
+        .unwrap_or_else(|| vec![record.name.clone()]);
+    let number = match record.layout.as_str() {
+        "meld" => None,
+        _ => record
+            .number
+            .as_ref()
+            .map(|number| number.trim_end_matches(char::is_alphabetic).to_string()),
+    };
+    (names, number)
+}
+
+/// Groups MTGJSON card records, such as the cards of a set, so that each
+/// group holds the records of the faces of one card, ordered as in its
+/// `names`. Groups are in the order each card first appears.
+pub fn group_cards(records: &[Card]) -> Vec<Vec<&Card>> {
+    let mut groups: Vec<Vec<&Card>> = vec![];
+    let mut index = HashMap::new();
+
+    for record in records {
+        let key = group_key(record);
+        let i = *index.entry(key).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[i].push(record);
+    }
+
+    for group in &mut groups {
+        let names = group_key(group[0]).0;
+        group.sort_by_key(|record| names.iter().position(|name| *name == record.name));
+    }
+
+    groups
+}
+
+/// Converts a group of records from `group_cards` into a card. The type
+/// lines are built from the typed arrays and checked against the printed
+/// `type`. A meld set gives a card for each of its two parts.
+pub fn convert_group(group: &[&Card]) -> Result<Vec<card::Card>, ConversionError> {
+    let mut converter = Converter { errors: vec![] };
+    let converted = converter.cards(group);
+
+    if converter.errors.is_empty() && !converted.is_empty() {
+        Ok(converted)
+    } else {
+        Err(ConversionError {
+            names: group_key(group[0]).0,
+            errors: converter.errors,
+        })
+    }
+}
+
+/// Groups and converts MTGJSON card records. See `group_cards` and
+/// `convert_group`.
+pub fn convert_cards(records: &[Card]) -> Vec<Result<card::Card, ConversionError>> {
+    let mut cards = vec![];
+    for group in group_cards(records) {
+        match convert_group(&group) {
+            Ok(converted) => cards.extend(converted.into_iter().map(Ok)),
+            Err(error) => cards.push(Err(error)),
+        }
+    }
+
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magic_core::traits::Named;
+    use serde_json::{json, Value};
+
+    /// An MTGJSON card record with `fields` replacing the defaults.
+    fn record(fields: Value) -> Card {
+        serde_json::from_value(crate::fixtures::card(fields)).unwrap()
+    }
+
+    fn fire_ice() -> Vec<Card> {
+        let names = json!(["Fire", "Ice"]);
+        let half = |name: &str, number: &str, mana_cost: &str| {
+            record(json!({
+                "layout": "split",
+                "name": name,
+                "names": names,
+                "number": number,
+                "manaCost": mana_cost,
+                "type": "Instant",
+                "types": ["Instant"],
+                "subtypes": [],
+                "power": null,
+                "toughness": null,
+            }))
+        };
+
+        vec![
+            half("Ice", "128b", "{1}{U}"),
+            half("Fire", "128a", "{1}{R}"),
+        ]
+    }
+
+    #[test]
+    fn groups_faces() {
+        let mut records = fire_ice();
+        records.insert(1, record(json!({})));
+
+        let cards: Vec<_> = convert_cards(&records)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].display_name(), "Fire // Ice");
+        assert!(matches!(cards[0], card::Card::Split { fuse: false, .. }));
+        assert_eq!(cards[1].name().unwrap(), "Goblin Guide");
+    }
+
+    #[test]
+    fn type_arrays_are_checked() {
+        let mismatched = record(json!({ "subtypes": ["Goblin", "Warrior"] }));
+        let error = convert_cards(&[mismatched]).remove(0).unwrap_err();
+
+        assert_eq!(error.errors()[0].field(), "type");
+        match error.errors()[0].kind() {
+            FieldErrorKind::TypeLineMismatch { arrays, printed } => {
+                assert_eq!(arrays.to_string(), "Creature \u{2014} Goblin Warrior");
+                assert_eq!(printed.to_string(), "Creature \u{2014} Goblin Scout");
+            }
+            kind => panic!("unexpected error {:?}", kind),
+        }
+
+        let unknown = record(json!({ "types": ["Creture"], "power": "x y" }));
+        let error = convert_cards(&[unknown]).remove(0).unwrap_err();
+        let fields: Vec<_> = error.errors().iter().map(FieldError::field).collect();
+        assert_eq!(fields, vec!["types", "power"]);
+    }
+
+    #[test]
+    fn multi_word_subtypes() {
+        let doctor = record(json!({
+            "name": "The Tenth Doctor",
+            "type": "Legendary Creature \u{2014} Time Lord Doctor",
+            "supertypes": ["Legendary"],
+            "subtypes": ["Time Lord", "Doctor"],
+        }));
+        let card = convert_cards(&[doctor]).remove(0).unwrap();
+
+        let type_line = card.faces().next().unwrap().type_line();
+        assert!(type_line.is_known());
+        assert_eq!(
+            type_line.to_string(),
+            "Legendary Creature \u{2014} Time Lord Doctor"
+        );
+    }
+
+    #[test]
+    fn newer_layouts() {
+        let mutate = record(json!({ "layout": "mutate" }));
+        let card = convert_cards(&[mutate]).remove(0).unwrap();
+        assert!(matches!(card, card::Card::Normal(_)));
+
+        let fleshgorger = record(json!({
+            "layout": "prototype",
+            "name": "Phyrexian Fleshgorger",
+            "manaCost": "{7}",
+            "type": "Artifact Creature \u{2014} Phyrexian Wurm",
+            "types": ["Artifact", "Creature"],
+            "subtypes": ["Phyrexian", "Wurm"],
+            "power": "7",
+            "toughness": "5",
+            "text": "Prototype {1}{B}{B} \u{2014} 3/3\nMenace, lifelink",
+        }));
+        match convert_cards(&[fleshgorger]).remove(0).unwrap() {
+            card::Card::Prototype { card, prototype } => {
+                assert_eq!(card.mana_cost().to_string(), "{7}");
+                assert_eq!(prototype.mana_cost().to_string(), "{1}{B}{B}");
+                assert_eq!(prototype.power().unwrap().to_string(), "3");
+            }
+            card => panic!("expected a prototype card, got {:?}", card),
+        }
+
+        let names = json!(["Invasion of Tarkir", "Defiant Thundermaw"]);
+        let battle = vec![
+            record(json!({
+                "layout": "battle",
+                "name": "Invasion of Tarkir",
+                "names": names,
+                "number": "149a",
+                "manaCost": "{1}{R}",
+                "type": "Battle \u{2014} Siege",
+                "types": ["Battle"],
+                "subtypes": ["Siege"],
+                "power": null,
+                "toughness": null,
+            })),
+            record(json!({
+                "layout": "battle",
+                "name": "Defiant Thundermaw",
+                "names": names,
+                "number": "149b",
+                "manaCost": "",
+                "colors": ["R"],
+                "type": "Creature \u{2014} Dragon",
+                "subtypes": ["Dragon"],
+                "power": "4",
+                "toughness": "4",
+            })),
+        ];
+        let card = convert_cards(&battle).remove(0).unwrap();
+        assert!(matches!(card, card::Card::DoubleFaced { modal: false, .. }));
+        assert_eq!(card.layout(), card::Layout::Battle);
+
+        let room: Vec<_> = [("Bottomless Pool", "{U}"), ("Locker Room", "{4}{U}")]
+            .iter()
+            .map(|&(name, mana_cost)| {
+                record(json!({
+                    "layout": "room",
+                    "name": name,
+                    "names": ["Bottomless Pool", "Locker Room"],
+                    "manaCost": mana_cost,
+                    "type": "Enchantment \u{2014} Room",
+                    "types": ["Enchantment"],
+                    "subtypes": ["Room"],
+                    "power": null,
+                    "toughness": null,
+                }))
+            })
+            .collect();
+        let card = convert_cards(&room).remove(0).unwrap();
+        assert!(matches!(card, card::Card::Room { .. }));
+        assert_eq!(card.display_name(), "Bottomless Pool // Locker Room");
+    }
+
+    #[test]
+    fn missing_faces() {
+        let records = fire_ice();
+        let error = convert_cards(&records[..1]).remove(0).unwrap_err();
+
+        assert_eq!(error.names(), ["Fire", "Ice"]);
+        assert_eq!(
+            error.errors()[0].kind(),
+            &FieldErrorKind::FaceCount {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn meld_sets() {
+        let names = json!([
+            "Bruna, the Fading Light",
+            "Brisela, Voice of Nightmares",
+            "Gisela, the Broken Blade"
+        ]);
+        let card = |name: &str, mana_cost: &str, subtypes: Value, type_: &str| {
+            record(json!({
+                "layout": "meld",
+                "name": name,
+                "names": names,
+                "manaCost": mana_cost,
+                "colors": ["W"],
+                "supertypes": ["Legendary"],
+                "subtypes": subtypes,
+                "type": type_,
+            }))
+        };
+
+        let records = vec![
+            card(
+                "Bruna, the Fading Light",
+                "{5}{W}{W}",
+                json!(["Angel", "Horror"]),
+                "Legendary Creature \u{2014} Angel Horror",
+            ),
+            card(
+                "Brisela, Voice of Nightmares",
+                "",
+                json!(["Eldrazi", "Angel"]),
+                "Legendary Creature \u{2014} Eldrazi Angel",
+            ),
+            card(
+                "Gisela, the Broken Blade",
+                "{2}{W}{W}",
+                json!(["Angel", "Horror"]),
+                "Legendary Creature \u{2014} Angel Horror",
+            ),
+        ];
+
+        let cards: Vec<_> = convert_cards(&records)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].melds_with(), Some("Gisela, the Broken Blade"));
+        assert_eq!(cards[1].melds_with(), Some("Bruna, the Fading Light"));
+        match &cards[0] {
+            card::Card::Meld { melded, .. } => {
+                assert_eq!(melded.color_indicator(), Some("W".parse().unwrap()));
+            }
+            _ => panic!("expected a meld card"),
+        }
+    }
+}
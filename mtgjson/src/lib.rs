@@ -1,11 +1,16 @@
 use chrono::NaiveDate;
 use hex::{FromHex, ToHex};
 use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
-use std::{collections::HashMap, fmt, str::FromStr};
+use std::{collections::HashMap, fmt};
 
-use magic_core::{
-    macros::{impl_deserialize_with_fromstr, impl_serialize_with_tostring},
-    Color,
+use magic_core::{mana::ManaCost, Color, ColorIdentity};
+
+mod convert;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+
+pub use self::convert::{
+    convert_cards, convert_group, group_cards, ConversionError, FieldError, FieldErrorKind,
 };
 
 #[derive(Debug, Copy, Clone)]
@@ -20,7 +25,7 @@ where
     where
         D: Deserializer<'de>,
     {
-        <&str>::deserialize(deserializer).and_then(|hex| {
+        String::deserialize(deserializer).and_then(|hex| {
             T::from_hex(hex)
                 .map(Hex)
                 .map_err(|err| de::Error::custom(err.to_string()))
@@ -36,79 +41,14 @@ where
     where
         S: Serializer,
     {
-        use std::error::Error;
-
         let mut buf = String::new();
         self.0
             .write_hex(&mut buf)
-            .map_err(|e| ser::Error::custom(e.description()))?;
+            .map_err(|e| ser::Error::custom(e.to_string()))?;
         serializer.serialize_str(&buf)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Abbreviated<T>(pub T);
-
-impl fmt::Display for Abbreviated<Color> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl fmt::Display for Abbreviated<Option<Color>> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.0 {
-            None => write!(f, "C"),
-            Some(c) => fmt::Display::fmt(&c, f),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ParseAbbreviatedColorError {
-    _priv: (),
-}
-
-impl fmt::Display for ParseAbbreviatedColorError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "provided string was a recognized abbreviated color")
-    }
-}
-
-impl FromStr for Abbreviated<Color> {
-    type Err = ParseAbbreviatedColorError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use self::Color::*;
-
-        match s {
-            "W" => Ok(White),
-            "U" => Ok(Blue),
-            "B" => Ok(Black),
-            "R" => Ok(Red),
-            "G" => Ok(Green),
-            _ => Err(ParseAbbreviatedColorError { _priv: () }),
-        }
-        .map(|c| Abbreviated(c))
-    }
-}
-
-impl FromStr for Abbreviated<Option<Color>> {
-    type Err = ParseAbbreviatedColorError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "C" => Ok(Abbreviated(None)),
-            s => Abbreviated::<Color>::from_str(s).map(|c| Abbreviated(Some(c.0))),
-        }
-    }
-}
-
-impl_serialize_with_tostring!(Abbreviated<Color>);
-impl_deserialize_with_fromstr!(Abbreviated<Color>);
-impl_serialize_with_tostring!(Abbreviated<Option<Color>>);
-impl_deserialize_with_fromstr!(Abbreviated<Option<Color>>);
-
 #[derive(Debug)]
 struct ShortenedDate(pub NaiveDate);
 
@@ -185,6 +125,20 @@ pub struct Set {
     type_: String,
 }
 
+impl Set {
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum BoosterItem {
@@ -199,7 +153,7 @@ pub struct Card {
     border: Option<String>,
     cmc: f32,
     #[serde(default)]
-    color_identity: Vec<Abbreviated<Color>>,
+    color_identity: ColorIdentity,
     #[serde(default)]
     colors: Vec<Color>,
     flavor: Option<String>,
@@ -211,7 +165,8 @@ pub struct Card {
     legalities: Option<Vec<FormatLegality>>,
     life: Option<i32>,
     loyalty: Option<i32>,
-    mana_cost: Option<String>,
+    #[serde(default, skip_serializing_if = "ManaCost::is_empty")]
+    mana_cost: ManaCost,
     mci_number: Option<String>,
     multiverseid: Option<i32>,
     name: String,
@@ -246,6 +201,29 @@ pub struct Card {
     watermark: Option<String>,
 }
 
+impl Card {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn number(&self) -> Option<&str> {
+        self.number.as_deref()
+    }
+
+    pub fn multiverseid(&self) -> Option<i32> {
+        self.multiverseid
+    }
+
+    /// The codes of the sets this card was printed in, oldest first.
+    pub fn printings(&self) -> &[String] {
+        &self.printings
+    }
+
+    pub fn legalities(&self) -> &[FormatLegality] {
+        self.legalities.as_deref().unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ForeignCard {
     language: String,
@@ -259,7 +237,17 @@ pub struct FormatLegality {
     legality: Legality,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl FormatLegality {
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn legality(&self) -> Legality {
+        self.legality
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Legality {
     Legal,
     Restricted,
@@ -295,7 +283,7 @@ pub struct Translations {
     zh_hant: Option<String>,
 }
 
-fn read_all_sets<P: AsRef<std::path::Path>>(
+pub fn read_all_sets<P: AsRef<std::path::Path>>(
     path: P,
 ) -> Result<Vec<Set>, Box<dyn std::error::Error>> {
     let json = std::fs::read_to_string(path)?;
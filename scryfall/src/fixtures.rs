@@ -0,0 +1,13 @@
+//! Card JSON for tests, also available to other crates with the
+//! `test-fixtures` feature.
+
+use serde_json::Value;
+
+/// A Scryfall card object for Goblin Guide, with `fields` replacing its own.
+pub fn card(fields: Value) -> Value {
+    let mut card: Value = serde_json::from_str(include_str!("../tests/data/card.json")).unwrap();
+    for (key, value) in fields.as_object().unwrap() {
+        card[key] = value.clone();
+    }
+    card
+}
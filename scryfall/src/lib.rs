@@ -1,2 +1,4 @@
 pub mod object;
 pub mod client;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
@@ -1,13 +1,22 @@
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
-use magic_core::Color;
+use magic_core::{
+    symbol::{self, ParseCardSymbolError},
+    Color, ColorIdentity,
+};
 use uuid::Uuid;
 
+mod convert;
+
+pub use self::convert::{meld, ConversionError, FieldError, FieldErrorKind};
+
 pub type Uri = String;
 pub type Date = String;
 pub type MimeType = String;
 pub type Encoding = String;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "object", rename_all = "snake_case")]
 pub enum Object {
@@ -127,16 +136,41 @@ pub struct CardSymbol {
     colors: Vec<Color>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl CardSymbol {
+    pub fn symbol(&self) -> &String {
+        &self.symbol
+    }
+
+    pub fn loose_variant(&self) -> Option<&String> {
+        self.loose_variant.as_ref()
+    }
+}
+
+impl TryFrom<&CardSymbol> for symbol::CardSymbol {
+    type Error = ParseCardSymbolError;
+
+    fn try_from(card_symbol: &CardSymbol) -> Result<Self, Self::Error> {
+        card_symbol.symbol.parse()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Layout {
     Normal,
     Split,
     Flip,
     Transform,
+    ModalDfc,
     Meld,
     Leveler,
+    Class,
+    Case,
     Saga,
+    Adventure,
+    Mutate,
+    Prototype,
+    Battle,
     Planar,
     Scheme,
     Vanguard,
@@ -145,6 +179,8 @@ pub enum Layout {
     Emblem,
     Augment,
     Host,
+    ArtSeries,
+    ReversibleCard,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -189,7 +225,10 @@ pub struct Card {
     cmc: f32,
     type_line: Option<String>,
     oracle_text: Option<String>,
-    mana_cost: Option<String>,
+    /// Parsed during conversion, so an unreadable cost fails one card
+    /// instead of a whole bulk data file.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    mana_cost: String,
     power: Option<String>,
     toughness: Option<String>,
     loyalty: Option<String>,
@@ -197,7 +236,7 @@ pub struct Card {
     hand_modifier: Option<String>,
     colors: Option<Vec<Color>>,
     color_indicator: Option<Vec<Color>>,
-    color_identity: Vec<Color>,
+    color_identity: ColorIdentity,
     all_parts: Option<Vec<Object>>,
     card_faces: Option<Vec<Object>>,
     legalities: Legalities,
@@ -239,20 +278,91 @@ impl Card {
     pub fn name(&self) -> &String {
         &self.name
     }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The id shared by every printing of this card.
+    pub fn oracle_id(&self) -> Uuid {
+        self.oracle_id
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn set(&self) -> &String {
+        &self.set
+    }
+
+    pub fn collector_number(&self) -> &String {
+        &self.collector_number
+    }
+
+    pub fn multiverse_ids(&self) -> &[u32] {
+        self.multiverse_ids.as_deref().unwrap_or_default()
+    }
+
+    pub fn mtgo_id(&self) -> Option<u32> {
+        self.mtgo_id
+    }
+
+    pub fn arena_id(&self) -> Option<u32> {
+        self.arena_id
+    }
+
+    pub fn legalities(&self) -> &Legalities {
+        &self.legalities
+    }
+
+    /// Whether this card was printed in an earlier set.
+    pub fn reprint(&self) -> bool {
+        self.reprint
+    }
+
+    /// The tokens, meld cards and combo pieces related to this card.
+    pub fn all_parts(&self) -> impl Iterator<Item = &RelatedCard> {
+        self.all_parts
+            .iter()
+            .flatten()
+            .filter_map(Object::as_related_card)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RelatedCard {
     id: Uuid,
+    component: Component,
     name: String,
     uri: Uri,
 }
 
+impl RelatedCard {
+    pub fn component(&self) -> Component {
+        self.component
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Component {
+    Token,
+    MeldPart,
+    MeldResult,
+    ComboPiece,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CardFace {
     name: String,
     type_line: String,
     oracle_text: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     mana_cost: String,
     colors: Option<Vec<Color>>,
     color_indicator: Option<Vec<Color>>,
@@ -274,7 +384,7 @@ pub struct ImageUris {
     small: Uri,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Legality {
     Legal,
@@ -300,6 +410,27 @@ pub struct Legalities {
     future: Legality,
 }
 
+impl Legalities {
+    /// The legality of the card in each format, keyed by Scryfall's format
+    /// names.
+    pub fn formats(&self) -> impl Iterator<Item = (&'static str, Legality)> {
+        vec![
+            ("standard", self.standard),
+            ("frontier", self.frontier),
+            ("modern", self.modern),
+            ("pauper", self.pauper),
+            ("legacy", self.legacy),
+            ("penny", self.penny),
+            ("vintage", self.vintage),
+            ("commander", self.commander),
+            ("duel", self.duel_commander),
+            ("1v1", self.commander_1v1),
+            ("future", self.future),
+        ]
+        .into_iter()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum SetType {
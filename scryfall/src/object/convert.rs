@@ -0,0 +1,676 @@
+//! Conversion of Scryfall cards into `magic_core::card::Card`.
+
+use super::{Card, CardFace, Component, Layout, Object};
+use magic_core::card::{
+    self, CardData, CardDataError, LoyaltyValue, ParseMode, ParseValueError, PtValue,
+    SpecialLayout, TypeLine, TypeLineReadError,
+};
+use magic_core::mana::{ManaCost, ParseManaCostError};
+use magic_core::type_::{EnchantmentType, Subtype};
+use magic_core::Color;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Why a field of a Scryfall card couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    /// A field the layout needs is absent.
+    Missing,
+    ManaCost(ParseManaCostError),
+    TypeLine(TypeLineReadError),
+    Value(ParseValueError),
+    /// The fields of a face parsed but don't make a valid card.
+    Face(CardDataError),
+    /// The layout needs a different number of faces.
+    FaceCount {
+        expected: usize,
+        found: usize,
+    },
+    /// A layout, such as an art series card, that isn't a Magic card.
+    UnsupportedLayout(Layout),
+    /// Meld cards are converted together with their meld result by `meld`.
+    Meld,
+}
+
+impl fmt::Display for FieldErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldErrorKind::Missing => write!(f, "missing"),
+            FieldErrorKind::ManaCost(error) => write!(f, "{}", error),
+            FieldErrorKind::TypeLine(error) => write!(f, "{}", error),
+            FieldErrorKind::Value(error) => write!(f, "{}", error),
+            FieldErrorKind::Face(error) => write!(f, "{}", error),
+            FieldErrorKind::FaceCount { expected, found } => {
+                write!(f, "expected {} faces, found {}", expected, found)
+            }
+            FieldErrorKind::UnsupportedLayout(layout) => {
+                write!(f, "unsupported layout {:?}", layout)
+            }
+            FieldErrorKind::Meld => write!(f, "meld cards need their meld result"),
+        }
+    }
+}
+
+/// A field of a Scryfall card that couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    face: Option<usize>,
+    field: &'static str,
+    kind: FieldErrorKind,
+}
+
+impl FieldError {
+    fn new(face: Option<usize>, field: &'static str, kind: FieldErrorKind) -> FieldError {
+        FieldError { face, field, kind }
+    }
+
+    /// The index in `card_faces` of the face with the error, if the error
+    /// is in a face.
+    pub fn face(&self) -> Option<usize> {
+        self.face
+    }
+
+    /// The name of the field in Scryfall's card object, such as
+    /// `"type_line"`.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    pub fn kind(&self) -> &FieldErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(face) = self.face {
+            write!(f, "card_faces[{}].", face)?;
+        }
+        write!(f, "{}: {}", self.field, self.kind)
+    }
+}
+
+/// Every field of a Scryfall card that couldn't be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    name: String,
+    errors: Vec<FieldError>,
+}
+
+impl ConversionError {
+    /// The name of the card, as Scryfall prints it.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "couldn't convert `{}`: ", self.name)?;
+        for (i, error) in self.errors.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { "; " } else { "" }, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// The fields shared by a card and each of its faces.
+struct Fields<'a> {
+    name: &'a str,
+    mana_cost: &'a str,
+    type_line: Option<&'a str>,
+    oracle_text: Option<&'a str>,
+    color_indicator: Option<&'a [Color]>,
+    power: Option<&'a str>,
+    toughness: Option<&'a str>,
+    loyalty: Option<&'a str>,
+}
+
+impl<'a> From<&'a Card> for Fields<'a> {
+    fn from(card: &'a Card) -> Fields<'a> {
+        Fields {
+            name: &card.name,
+            mana_cost: &card.mana_cost,
+            type_line: card.type_line.as_deref(),
+            oracle_text: card.oracle_text.as_deref(),
+            color_indicator: card.color_indicator.as_deref(),
+            power: card.power.as_deref(),
+            toughness: card.toughness.as_deref(),
+            loyalty: card.loyalty.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a CardFace> for Fields<'a> {
+    fn from(face: &'a CardFace) -> Fields<'a> {
+        Fields {
+            name: &face.name,
+            mana_cost: &face.mana_cost,
+            type_line: Some(&face.type_line),
+            oracle_text: face.oracle_text.as_deref(),
+            color_indicator: face.color_indicator.as_deref(),
+            power: face.power.as_deref(),
+            toughness: face.toughness.as_deref(),
+            loyalty: face.loyalty.as_deref(),
+        }
+    }
+}
+
+/// Collects the errors of one card while its faces are converted.
+struct Converter {
+    errors: Vec<FieldError>,
+}
+
+impl Converter {
+    fn error(&mut self, face: Option<usize>, field: &'static str, kind: FieldErrorKind) {
+        self.errors.push(FieldError::new(face, field, kind));
+    }
+
+    /// Parses `value` with `parse`, recording an error if it fails.
+    fn parse<T, E>(
+        &mut self,
+        face: Option<usize>,
+        field: &'static str,
+        parse: impl FnOnce() -> Result<T, E>,
+        kind: impl FnOnce(E) -> FieldErrorKind,
+    ) -> Option<T> {
+        match parse() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.error(face, field, kind(error));
+                None
+            }
+        }
+    }
+
+    /// Parses a printed value, if there is one.
+    fn value<T>(
+        &mut self,
+        face: Option<usize>,
+        field: &'static str,
+        value: Option<&str>,
+    ) -> Option<T>
+    where
+        T: FromStr<Err = ParseValueError>,
+    {
+        let value = value?;
+        self.parse(face, field, || value.parse(), FieldErrorKind::Value)
+    }
+
+    /// Converts one face, reporting every field that fails.
+    fn face(&mut self, fields: Fields, face: Option<usize>) -> Option<CardData> {
+        let errors = self.errors.len();
+
+        let mana_cost = self.parse(
+            face,
+            "mana_cost",
+            || fields.mana_cost.parse::<ManaCost>(),
+            FieldErrorKind::ManaCost,
+        );
+
+        let type_line = match fields.type_line {
+            Some(type_line) => self.parse(
+                face,
+                "type_line",
+                || TypeLine::parse(type_line, ParseMode::Lenient),
+                FieldErrorKind::TypeLine,
+            ),
+            None => {
+                self.error(face, "type_line", FieldErrorKind::Missing);
+                None
+            }
+        };
+
+        let power: Option<PtValue> = self.value(face, "power", fields.power);
+        let toughness: Option<PtValue> = self.value(face, "toughness", fields.toughness);
+        let loyalty: Option<LoyaltyValue> = self.value(face, "loyalty", fields.loyalty);
+
+        match (fields.power, fields.toughness) {
+            (Some(_), None) => self.error(face, "toughness", FieldErrorKind::Missing),
+            (None, Some(_)) => self.error(face, "power", FieldErrorKind::Missing),
+            _ => {}
+        }
+
+        if self.errors.len() > errors {
+            return None;
+        }
+
+        let mut builder = CardData::builder(fields.name)
+            .mana_cost(mana_cost?)
+            .type_line(type_line?)
+            .text(fields.oracle_text.unwrap_or_default());
+        if let Some(colors) = fields.color_indicator {
+            builder = builder.color_indicator(colors.iter().cloned().collect());
+        }
+        if let (Some(power), Some(toughness)) = (power, toughness) {
+            builder = builder.power_toughness(power, toughness);
+        }
+        if let Some(loyalty) = loyalty {
+            builder = builder.loyalty(loyalty);
+        }
+
+        match builder.build() {
+            Ok(card_data) => Some(card_data),
+            Err(error) => {
+                self.error(face, card_data_field(&error), FieldErrorKind::Face(error));
+                None
+            }
+        }
+    }
+
+    /// Converts the two faces of `card`.
+    fn two_faces(&mut self, card: &Card) -> Option<(CardData, CardData)> {
+        let faces: Vec<&CardFace> = card
+            .card_faces
+            .iter()
+            .flatten()
+            .filter_map(Object::as_card_face)
+            .collect();
+        if faces.len() != 2 {
+            let kind = FieldErrorKind::FaceCount {
+                expected: 2,
+                found: faces.len(),
+            };
+            self.error(None, "card_faces", kind);
+            return None;
+        }
+
+        let first = self.face(faces[0].into(), Some(0));
+        let second = self.face(faces[1].into(), Some(1));
+        Some((first?, second?))
+    }
+
+    fn card(&mut self, card: &Card) -> Option<card::Card> {
+        let special = |converter: &mut Converter, kind| {
+            let face = converter.face(card.into(), None)?;
+            Some(card::Card::Special { kind, face })
+        };
+
+        let converted = match card.layout {
+            Layout::Normal | Layout::Mutate => card::Card::Normal(self.face(card.into(), None)?),
+            Layout::Leveler => special(self, SpecialLayout::Leveler)?,
+            Layout::Saga => special(self, SpecialLayout::Saga)?,
+            Layout::Class => special(self, SpecialLayout::Class)?,
+            Layout::Case => special(self, SpecialLayout::Case)?,
+            Layout::Planar => special(self, SpecialLayout::Planar)?,
+            Layout::Scheme => special(self, SpecialLayout::Scheme)?,
+            Layout::Vanguard => special(self, SpecialLayout::Vanguard)?,
+            Layout::Token => special(self, SpecialLayout::Token)?,
+            Layout::Emblem => special(self, SpecialLayout::Emblem)?,
+            Layout::Augment => special(self, SpecialLayout::Augment)?,
+            Layout::Host => special(self, SpecialLayout::Host)?,
+            Layout::Split => {
+                let (left, right) = self.two_faces(card)?;
+                split(left, right)
+            }
+            Layout::Flip => {
+                let (top, bottom) = self.two_faces(card)?;
+                card::Card::Flip { top, bottom }
+            }
+            Layout::Transform | Layout::Battle | Layout::DoubleFacedToken => {
+                let (front, back) = self.two_faces(card)?;
+                card::Card::DoubleFaced {
+                    front,
+                    back,
+                    modal: false,
+                }
+            }
+            Layout::ModalDfc => {
+                let (front, back) = self.two_faces(card)?;
+                card::Card::DoubleFaced {
+                    front,
+                    back,
+                    modal: true,
+                }
+            }
+            Layout::Adventure => {
+                let (creature, adventure) = self.two_faces(card)?;
+                card::Card::Adventure {
+                    creature,
+                    adventure,
+                }
+            }
+            Layout::Prototype => self.prototype(card)?,
+            Layout::Meld => {
+                self.error(None, "layout", FieldErrorKind::Meld);
+                return None;
+            }
+            Layout::ArtSeries | Layout::ReversibleCard => {
+                let kind = FieldErrorKind::UnsupportedLayout(card.layout);
+                self.error(None, "layout", kind);
+                return None;
+            }
+        };
+
+        Some(converted)
+    }
+
+    /// Scryfall gives a prototype card one face, with the prototype's cost
+    /// and power and toughness in its rules text:
+    /// `Prototype {1}{B}{B} — 3/3`.
+    fn prototype(&mut self, card: &Card) -> Option<card::Card> {
+        let main = self.face(card.into(), None)?;
+
+        let prototype = main.text().lines().find_map(|line| {
+            let (mana_cost, pt) = line.strip_prefix("Prototype ")?.split_once(" \u{2014} ")?;
+            let (power, toughness) = pt.split_once('/')?;
+            Some((mana_cost, power, toughness))
+        });
+        let (mana_cost, power, toughness) = match prototype {
+            Some(prototype) => prototype,
+            None => {
+                self.error(None, "oracle_text", FieldErrorKind::Missing);
+                return None;
+            }
+        };
+
+        let fields = Fields {
+            mana_cost,
+            power: Some(power),
+            toughness: Some(toughness),
+            ..Fields::from(card)
+        };
+        let prototype = self.face(fields, None)?;
+
+        Some(card::Card::Prototype {
+            card: main,
+            prototype,
+        })
+    }
+}
+
+/// The Scryfall field a `CardDataError` is about.
+fn card_data_field(error: &CardDataError) -> &'static str {
+    match error {
+        CardDataError::EmptyName => "name",
+        CardDataError::InvalidTypeLine(_) => "type_line",
+        CardDataError::UnexpectedPowerToughness => "power",
+        CardDataError::IncompletePowerToughness => "toughness",
+        CardDataError::UnexpectedLoyalty => "loyalty",
+    }
+}
+
+/// Scryfall gives aftermath cards and rooms the split layout.
+fn split(left: CardData, right: CardData) -> card::Card {
+    let room = Subtype::Enchantment(EnchantmentType::Room);
+    let keyword =
+        |face: &CardData, keyword| face.text().lines().any(|line| line.starts_with(keyword));
+
+    if left.type_line().has_subtype(room.clone()) && right.type_line().has_subtype(room) {
+        card::Card::Room { left, right }
+    } else if keyword(&right, "Aftermath") {
+        card::Card::Aftermath {
+            top: left,
+            bottom: right,
+        }
+    } else {
+        let fuse = keyword(&left, "Fuse") || keyword(&right, "Fuse");
+        card::Card::Split { left, right, fuse }
+    }
+}
+
+impl TryFrom<&Card> for card::Card {
+    type Error = ConversionError;
+
+    /// Picks the `Card` variant from the layout and converts each face,
+    /// parsing type lines in `ParseMode::Lenient`. Meld cards are
+    /// converted by `meld` instead.
+    fn try_from(card: &Card) -> Result<card::Card, ConversionError> {
+        let mut converter = Converter { errors: vec![] };
+        match converter.card(card) {
+            Some(converted) if converter.errors.is_empty() => Ok(converted),
+            _ => Err(ConversionError {
+                name: card.name.clone(),
+                errors: converter.errors,
+            }),
+        }
+    }
+}
+
+/// Converts one card of a meld pair, given the card it melds into.
+pub fn meld(part: &Card, result: &Card) -> Result<card::Card, ConversionError> {
+    let mut converter = Converter { errors: vec![] };
+
+    let front = converter.face(part.into(), None);
+    let melded = converter.face(result.into(), None);
+    let partner = part
+        .all_parts()
+        .find(|related| related.component == Component::MeldPart && related.name != part.name);
+    if partner.is_none() {
+        converter.error(None, "all_parts", FieldErrorKind::Missing);
+    }
+
+    match (front, melded, partner) {
+        (Some(front), Some(melded), Some(partner)) if converter.errors.is_empty() => {
+            Ok(card::Card::Meld {
+                front,
+                melded,
+                partner: partner.name.clone(),
+            })
+        }
+        _ => Err(ConversionError {
+            name: part.name.clone(),
+            errors: converter.errors,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magic_core::traits::Named;
+    use serde_json::{json, Value};
+
+    /// A Scryfall card object with `fields` replacing the defaults.
+    fn card(fields: Value) -> Card {
+        serde_json::from_value(crate::fixtures::card(fields)).unwrap()
+    }
+
+    fn face(name: &str, mana_cost: &str, type_line: &str, oracle_text: &str) -> Value {
+        json!({
+            "object": "card_face",
+            "name": name,
+            "mana_cost": mana_cost,
+            "type_line": type_line,
+            "oracle_text": oracle_text
+        })
+    }
+
+    #[test]
+    fn normal_card() {
+        let converted = card::Card::try_from(&card(json!({}))).unwrap();
+
+        let face = match &converted {
+            card::Card::Normal(face) => face,
+            _ => panic!("expected a normal card"),
+        };
+        assert_eq!(face.mana_cost().to_string(), "{R}");
+        assert_eq!(face.power(), Some(&PtValue::Number(2)));
+        assert_eq!(face.text(), "Haste");
+    }
+
+    #[test]
+    fn split_layouts() {
+        let fire_ice = card(json!({
+            "name": "Fire // Ice",
+            "layout": "split",
+            "type_line": "Instant // Instant",
+            "card_faces": [
+                face("Fire", "{1}{R}", "Instant", "Fire deals 2 damage divided as you choose."),
+                face("Ice", "{1}{U}", "Instant", "Tap target permanent.\nDraw a card."),
+            ]
+        }));
+        let converted = card::Card::try_from(&fire_ice).unwrap();
+        assert!(matches!(converted, card::Card::Split { fuse: false, .. }));
+        assert_eq!(converted.display_name(), "Fire // Ice");
+
+        let cut_ribbons = card(json!({
+            "name": "Cut // Ribbons",
+            "layout": "split",
+            "card_faces": [
+                face("Cut", "{1}{R}", "Sorcery", "Cut deals 4 damage to target creature."),
+                face(
+                    "Ribbons",
+                    "{X}{B}{B}",
+                    "Sorcery",
+                    "Aftermath (Cast this spell only from your graveyard.)\nEach opponent loses X life."
+                ),
+            ]
+        }));
+        let converted = card::Card::try_from(&cut_ribbons).unwrap();
+        assert!(matches!(converted, card::Card::Aftermath { .. }));
+    }
+
+    #[test]
+    fn double_faced_and_prototype() {
+        let delver = card(json!({
+            "name": "Delver of Secrets // Insectile Aberration",
+            "layout": "transform",
+            "card_faces": [
+                {
+                    "object": "card_face",
+                    "name": "Delver of Secrets",
+                    "mana_cost": "{U}",
+                    "type_line": "Creature \u{2014} Human Wizard",
+                    "power": "1",
+                    "toughness": "1"
+                },
+                {
+                    "object": "card_face",
+                    "name": "Insectile Aberration",
+                    "mana_cost": "",
+                    "type_line": "Creature \u{2014} Human Insect",
+                    "color_indicator": ["U"],
+                    "power": "3",
+                    "toughness": "2"
+                }
+            ]
+        }));
+        let converted = card::Card::try_from(&delver).unwrap();
+        assert!(matches!(
+            converted,
+            card::Card::DoubleFaced { modal: false, .. }
+        ));
+        assert_eq!(converted.name().unwrap(), "Delver of Secrets");
+
+        let fleshgorger = card(json!({
+            "name": "Phyrexian Fleshgorger",
+            "layout": "prototype",
+            "mana_cost": "{7}",
+            "type_line": "Artifact Creature \u{2014} Phyrexian Wurm",
+            "oracle_text": "Prototype {1}{B}{B} \u{2014} 3/3\nMenace, lifelink, ward\u{2014}Pay life equal to Phyrexian Fleshgorger's power.",
+            "power": "7",
+            "toughness": "5"
+        }));
+        match card::Card::try_from(&fleshgorger).unwrap() {
+            card::Card::Prototype { card, prototype } => {
+                assert_eq!(card.mana_cost().to_string(), "{7}");
+                assert_eq!(prototype.mana_cost().to_string(), "{1}{B}{B}");
+                assert_eq!(prototype.power(), Some(&PtValue::Number(3)));
+            }
+            _ => panic!("expected a prototype card"),
+        }
+    }
+
+    #[test]
+    fn every_failed_field_is_reported() {
+        let broken = card(json!({
+            "name": "Fire // Ice",
+            "layout": "split",
+            "card_faces": [
+                face("Fire", "{1}{R", "Instant", ""),
+                {
+                    "object": "card_face",
+                    "name": "Ice",
+                    "mana_cost": "{1}{U}",
+                    "type_line": "Instant",
+                    "power": "1 + *"
+                },
+            ]
+        }));
+        let error = card::Card::try_from(&broken).unwrap_err();
+
+        let fields: Vec<_> = error
+            .errors()
+            .iter()
+            .map(|error| (error.face(), error.field()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (Some(0), "mana_cost"),
+                (Some(1), "power"),
+                (Some(1), "toughness"),
+            ]
+        );
+        assert!(error
+            .to_string()
+            .starts_with("couldn't convert `Fire // Ice`: "));
+
+        let bad_cost = card(json!({ "mana_cost": "{1}{R" }));
+        let error = card::Card::try_from(&bad_cost).unwrap_err();
+        assert_eq!(error.errors()[0].field(), "mana_cost");
+        assert!(matches!(
+            error.errors()[0].kind(),
+            FieldErrorKind::ManaCost(_)
+        ));
+
+        let bear = card(json!({ "type_line": "Sorcery", "loyalty": "3" }));
+        let error = card::Card::try_from(&bear).unwrap_err();
+        assert_eq!(error.errors()[0].field(), "power");
+        assert_eq!(
+            error.errors()[0].kind(),
+            &FieldErrorKind::Face(CardDataError::UnexpectedPowerToughness)
+        );
+    }
+
+    #[test]
+    fn meld_pairs() {
+        let related = |name: &str, component: &str| {
+            json!({
+                "object": "related_card",
+                "id": "0000579f-7b35-4ed3-b44c-db2a538066fe",
+                "component": component,
+                "name": name,
+                "uri": ""
+            })
+        };
+        let all_parts = json!([
+            related("Bruna, the Fading Light", "meld_part"),
+            related("Gisela, the Broken Blade", "meld_part"),
+            related("Brisela, Voice of Nightmares", "meld_result"),
+        ]);
+
+        let bruna = card(json!({
+            "name": "Bruna, the Fading Light",
+            "layout": "meld",
+            "mana_cost": "{5}{W}{W}",
+            "type_line": "Legendary Creature \u{2014} Angel Horror",
+            "power": "5",
+            "toughness": "7",
+            "all_parts": all_parts,
+        }));
+        let brisela = card(json!({
+            "name": "Brisela, Voice of Nightmares",
+            "layout": "meld",
+            "mana_cost": "",
+            "type_line": "Legendary Creature \u{2014} Eldrazi Angel",
+            "power": "9",
+            "toughness": "10",
+            "all_parts": all_parts,
+        }));
+
+        let error = card::Card::try_from(&bruna).unwrap_err();
+        assert_eq!(error.errors()[0].kind(), &FieldErrorKind::Meld);
+
+        let converted = meld(&bruna, &brisela).unwrap();
+        assert_eq!(converted.melds_with(), Some("Gisela, the Broken Blade"));
+    }
+}